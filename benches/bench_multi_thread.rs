@@ -0,0 +1,73 @@
+#![feature(test)]
+extern crate test;
+
+extern crate flexi_logger;
+extern crate log;
+
+use flexi_logger::detailed_format;
+use flexi_logger::writers::{FileLogWriter, LogWriter};
+use log::{Level, Record};
+use std::sync::Arc;
+use std::thread;
+use test::Bencher;
+
+const NO_OF_LOGLINES: usize = 4_000;
+
+// Writes the same number of log lines with one and with several threads,
+// to show how the throughput of a single FileLogWriter scales with concurrent callers.
+
+#[bench]
+fn b10_one_thread(b: &mut Bencher) {
+    bench_with_threads(b, 1);
+}
+
+#[bench]
+fn b20_four_threads(b: &mut Bencher) {
+    bench_with_threads(b, 4);
+}
+
+#[bench]
+fn b30_eight_threads(b: &mut Bencher) {
+    bench_with_threads(b, 8);
+}
+
+fn bench_with_threads(b: &mut Bencher, no_of_threads: usize) {
+    let writer = Arc::new(
+        FileLogWriter::builder()
+            .directory("log_files/bench_multi_thread")
+            .discriminant(format!("{}_threads", no_of_threads))
+            .format(detailed_format)
+            .rotate_over_size(10_000_000)
+            .max_backup(1)
+            .instantiate()
+            .unwrap_or_else(|e| panic!("FileLogWriter initialization failed with {}", e)),
+    );
+    b.iter(|| {
+        let worker_handles: Vec<_> = (0..no_of_threads)
+            .map(|thread_number| {
+                let writer = Arc::clone(&writer);
+                thread::spawn(move || {
+                    for idx in 0..NO_OF_LOGLINES / no_of_threads {
+                        writer
+                            .write(
+                                &Record::builder()
+                                    .args(format_args!(
+                                        "({}) writing out line number {} of the benchmark",
+                                        thread_number, idx
+                                    ))
+                                    .level(Level::Info)
+                                    .module_path(Some("bench_multi_thread"))
+                                    .file(Some("benches/bench_multi_thread.rs"))
+                                    .line(Some(42))
+                                    .build(),
+                            )
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for worker_handle in worker_handles {
+            worker_handle.join().unwrap();
+        }
+    });
+}
@@ -0,0 +1,105 @@
+use atty;
+use log::{Level, Record};
+use std::env;
+use std::io;
+use formatter::Formatter;
+use FlexiLoggerError;
+
+/// Defines the colors that are used for log lines on stderr and stdout, per log level.
+///
+/// Each color is an ANSI 256-color code (see e.g.
+/// [the 256-color table](https://en.wikipedia.org/wiki/ANSI_escape_code#8-bit)),
+/// or `None` for leaving the log lines of the level uncolored.
+///
+/// See [`Logger::colored()`](struct.Logger.html#method.colored).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Palette {
+    /// Color for error messages; default is bright red (196).
+    pub error: Option<u8>,
+    /// Color for warnings; default is orange (208).
+    pub warn: Option<u8>,
+    /// Color for info messages; default is uncolored.
+    pub info: Option<u8>,
+    /// Color for debug messages; default is light grey (7).
+    pub debug: Option<u8>,
+    /// Color for trace messages; default is grey (8).
+    pub trace: Option<u8>,
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        Palette {
+            error: Some(196),
+            warn: Some(208),
+            info: None,
+            debug: Some(7),
+            trace: Some(8),
+        }
+    }
+}
+
+impl Palette {
+    /// Parses a palette from a String like `"196;208;-;7;8"`,
+    /// with the colors for error, warn, info, debug, and trace, in this order,
+    /// where `-` leaves the level uncolored.
+    pub fn parse<S: AsRef<str>>(s: S) -> Result<Palette, FlexiLoggerError> {
+        let parts: Vec<&str> = s.as_ref().split(';').map(|p| p.trim()).collect();
+        if parts.len() != 5 {
+            return Err(FlexiLoggerError::Parse(format!(
+                "a palette needs five colors, separated by ';', found \"{}\"",
+                s.as_ref()
+            )));
+        }
+        let mut colors = [None; 5];
+        for (color, part) in colors.iter_mut().zip(parts) {
+            if part != "-" {
+                *color = Some(part.parse::<u8>().map_err(|_| {
+                    FlexiLoggerError::Parse(format!("invalid color in palette: \"{}\"", part))
+                })?);
+            }
+        }
+        Ok(Palette {
+            error: colors[0],
+            warn: colors[1],
+            info: colors[2],
+            debug: colors[3],
+            trace: colors[4],
+        })
+    }
+
+    fn color(&self, level: Level) -> Option<u8> {
+        match level {
+            Level::Error => self.error,
+            Level::Warn => self.warn,
+            Level::Info => self.info,
+            Level::Debug => self.debug,
+            Level::Trace => self.trace,
+        }
+    }
+}
+
+// Writes the log line with the given formatter, in the color of its level, if any.
+pub fn write_colored<W: io::Write>(
+    palette: &Palette,
+    formatter: &Formatter,
+    w: &mut W,
+    record: &Record,
+) -> io::Result<()> {
+    match palette.color(record.level()) {
+        None => formatter.format(w, record),
+        Some(color) => {
+            write!(w, "\x1b[38;5;{}m", color)?;
+            formatter.format(w, record)?;
+            w.write_all(b"\x1b[0m")
+        }
+    }
+}
+
+// Colors are only used if the stream is a terminal and the environment variable NO_COLOR
+// is not set.
+pub fn supports_colors(stream: atty::Stream) -> bool {
+    match env::var_os("NO_COLOR") {
+        Some(ref value) if !value.is_empty() => false,
+        _ => atty::is(stream),
+    }
+}
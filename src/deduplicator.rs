@@ -0,0 +1,148 @@
+use log::{Level, Record};
+use std::io;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Suppresses consecutive identical log lines.
+//
+// A log line is identical to its predecessor if it has the same level, module path,
+// and message. Identical log lines are suppressed until the window, which starts with the
+// first log line of the run, has passed. When the run ends, because a different log line
+// comes, or an identical log line comes after the window, or the logger is flushed,
+// a summary line with the number of suppressed log lines is written, if there were any.
+pub struct Deduplicator {
+    window: Duration,
+    o_run: Mutex<Option<Run>>,
+}
+
+// The log line that was written last, and the number of suppressed repetitions of it.
+struct Run {
+    level: Level,
+    target: String,
+    module_path: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    message: String,
+    start: Instant,
+    repeated: usize,
+}
+
+impl Run {
+    fn new(record: &Record, message: String) -> Run {
+        Run {
+            level: record.level(),
+            target: record.target().to_string(),
+            module_path: record.module_path().map(|s| s.to_string()),
+            file: record.file().map(|s| s.to_string()),
+            line: record.line(),
+            message,
+            start: Instant::now(),
+            repeated: 0,
+        }
+    }
+
+    fn is_repeated_by(&self, record: &Record, message: &str) -> bool {
+        self.level == record.level()
+            && self.module_path.as_deref() == record.module_path()
+            && self.message == message
+    }
+
+    // Ends the run; returns the summary line, if log lines were suppressed.
+    fn finish(&mut self) -> Option<Summary> {
+        if self.repeated == 0 {
+            return None;
+        }
+        let repeated = self.repeated;
+        self.repeated = 0;
+        Some(Summary {
+            level: self.level,
+            target: self.target.clone(),
+            module_path: self.module_path.clone(),
+            file: self.file.clone(),
+            line: self.line,
+            repeated,
+        })
+    }
+}
+
+// The summary line of a run, which is written after the lock on the run is released.
+struct Summary {
+    level: Level,
+    target: String,
+    module_path: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    repeated: usize,
+}
+
+impl Summary {
+    fn write<F: Fn(&Record) -> io::Result<()>>(&self, write: &F) -> io::Result<()> {
+        write(
+            &Record::builder()
+                .args(format_args!("last message repeated {} times", self.repeated))
+                .level(self.level)
+                .target(&self.target)
+                .module_path(self.module_path.as_deref())
+                .file(self.file.as_deref())
+                .line(self.line)
+                .build(),
+        )
+    }
+}
+
+impl Deduplicator {
+    pub fn new(window: Duration) -> Deduplicator {
+        Deduplicator {
+            window,
+            o_run: Mutex::new(None),
+        }
+    }
+
+    // Writes the record with the given function, unless it repeats the previous one;
+    // returns false if the record was suppressed.
+    //
+    // The run is only locked while it is compared and updated, not while writing.
+    pub fn write<F: Fn(&Record) -> io::Result<()>>(
+        &self,
+        record: &Record,
+        write: F,
+    ) -> io::Result<bool> {
+        let message = record.args().to_string();
+        let o_summary = {
+            let mut guard = self.o_run.lock().unwrap(/* ok */);
+            match *guard {
+                Some(ref mut run) if run.is_repeated_by(record, &message) => {
+                    if run.start.elapsed() < self.window {
+                        run.repeated += 1;
+                        return Ok(false);
+                    }
+                    run.start = Instant::now();
+                    run.finish()
+                }
+                _ => {
+                    let o_summary = guard.as_mut().and_then(|run| run.finish());
+                    *guard = Some(Run::new(record, message));
+                    o_summary
+                }
+            }
+        };
+        if let Some(summary) = o_summary {
+            summary.write(&write)?;
+        }
+        write(record).map(|()| true)
+    }
+
+    // Writes the summary line of the current run, if log lines were suppressed.
+    pub fn flush<F: Fn(&Record) -> io::Result<()>>(&self, write: F) -> io::Result<()> {
+        let o_summary = self
+            .o_run
+            .lock()
+            .unwrap(/* ok */)
+            .as_mut()
+            .and_then(|run| run.finish());
+        match o_summary {
+            Some(summary) => summary.write(&write),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,281 @@
+#![allow(unknown_lints)]
+#![allow(clippy::missing_const_for_thread_local)]
+
+use limiter::{Admission, Limiter};
+use log::{Level, Record};
+use statistics::{increment, LoggerCounters};
+use std::cell::RefCell;
+use std::fmt;
+use std::io;
+use std::sync::{Arc, RwLock};
+use writers::LogWriter;
+use ModuleLimit;
+
+/// An error that occurred while the logger was writing or flushing log lines,
+/// as it is passed to the [`ErrorPolicy`](enum.ErrorPolicy.html).
+#[derive(Debug)]
+pub enum LoggingError {
+    /// Writing a log line failed.
+    Write {
+        /// The name of the writer: `"primary_writer"`, `"ring_buffer"`,
+        /// or the name of an additional writer.
+        writer: String,
+        /// The error of the writer.
+        error: io::Error,
+    },
+    /// Flushing a writer failed.
+    Flush {
+        /// The name of the writer, see `Write`.
+        writer: String,
+        /// The error of the writer.
+        error: io::Error,
+    },
+    /// The target of a log call named a writer that does not exist.
+    UnknownWriter(String),
+    /// So many errors were not reported due to the limit for error reports,
+    /// see [`Logger::limit_error_reports()`](struct.Logger.html#method.limit_error_reports).
+    Unreported(usize),
+}
+
+impl fmt::Display for LoggingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LoggingError::Write {
+                ref writer,
+                ref error,
+            } => write!(f, "writing log line to {} failed with {}", writer, error),
+            LoggingError::Flush {
+                ref writer,
+                ref error,
+            } => write!(f, "flushing {} failed with {}", writer, error),
+            LoggingError::UnknownWriter(ref name) => write!(f, "bad writer spec: {}", name),
+            LoggingError::Unreported(count) => {
+                write!(f, "{} further errors were not reported", count)
+            }
+        }
+    }
+}
+
+/// Describes how the logger deals with errors of its writers, and with log calls
+/// that address unknown writers,
+/// see [`Logger::error_policy()`](struct.Logger.html#method.error_policy).
+///
+/// All errors are also counted in the [`Statistics`](struct.Statistics.html).
+pub enum ErrorPolicy {
+    /// Errors are printed to stderr; this is the default.
+    StdErr,
+    /// Errors are passed to the given function.
+    Callback(Box<dyn Fn(&LoggingError) + Send + Sync>),
+    /// Log lines that could not be written are written to the given writer instead,
+    /// and errors are written to it as warnings, with target `flexi_logger`.
+    Fallback(Box<dyn LogWriter>),
+    /// The logger panics.
+    Panic,
+    /// Errors are only counted.
+    Silent,
+}
+
+// Applies the ErrorPolicy, and the limit for error reports, if there is one.
+pub struct ErrorHandler {
+    policy: ErrorPolicy,
+    o_limiter: Option<Limiter>,
+}
+
+impl ErrorHandler {
+    pub fn new(policy: ErrorPolicy, o_limit: Option<ModuleLimit>) -> ErrorHandler {
+        ErrorHandler {
+            policy,
+            o_limiter: o_limit.map(|limit| Limiter::new(None, limit)),
+        }
+    }
+
+    // Handles the error; the record is the log line that could not be written, if any.
+    pub fn handle(&self, error: LoggingError, o_record: Option<&Record>) {
+        match self.policy {
+            ErrorPolicy::Silent => return,
+            ErrorPolicy::Panic => panic!("FlexiLogger: {}", error),
+            ErrorPolicy::Fallback(ref writer) => {
+                if let Some(record) = o_record {
+                    writer.write(record).unwrap_or_else(|e| {
+                        eprintln!(
+                            "FlexiLogger: writing log line to fallback writer failed with {}",
+                            e
+                        );
+                    });
+                }
+            }
+            ErrorPolicy::StdErr | ErrorPolicy::Callback(_) => {}
+        }
+        if let Some(ref limiter) = self.o_limiter {
+            match limiter.admit() {
+                Admission::Pass => {}
+                Admission::PassWithNote(unreported) => {
+                    self.report(&LoggingError::Unreported(unreported))
+                }
+                Admission::Drop => return,
+            }
+        }
+        self.report(&error);
+    }
+
+    // Reports the errors that were not yet reported due to the limit, and flushes
+    // the fallback writer, if there is one.
+    pub fn flush(&self) {
+        if let Some(ref limiter) = self.o_limiter {
+            let unreported = limiter.take_dropped();
+            if unreported > 0 {
+                self.report(&LoggingError::Unreported(unreported));
+            }
+        }
+        if let ErrorPolicy::Fallback(ref writer) = self.policy {
+            writer.flush().unwrap_or_else(|e| {
+                eprintln!("FlexiLogger: flushing fallback writer failed with {}", e);
+            });
+        }
+    }
+
+    fn report(&self, error: &LoggingError) {
+        match self.policy {
+            ErrorPolicy::StdErr => eprintln!("FlexiLogger: {}", error),
+            ErrorPolicy::Callback(ref callback) => callback(error),
+            ErrorPolicy::Fallback(ref writer) => writer
+                .write(
+                    &Record::builder()
+                        .args(format_args!("{}", error))
+                        .level(Level::Warn)
+                        .target("flexi_logger")
+                        .build(),
+                )
+                .unwrap_or_else(|e| {
+                    eprintln!(
+                        "FlexiLogger: writing log line to fallback writer failed with {}",
+                        e
+                    );
+                }),
+            ErrorPolicy::Panic | ErrorPolicy::Silent => {}
+        }
+    }
+}
+
+// Counts the errors of a writer and passes them to the ErrorHandler of the logger.
+#[derive(Clone)]
+pub struct ErrorTarget {
+    handler: Arc<ErrorHandler>,
+    counters: Arc<LoggerCounters>,
+    writer: String,
+}
+
+impl ErrorTarget {
+    pub fn new(
+        handler: Arc<ErrorHandler>,
+        counters: Arc<LoggerCounters>,
+        writer: &str,
+    ) -> ErrorTarget {
+        ErrorTarget {
+            handler,
+            counters,
+            writer: writer.to_string(),
+        }
+    }
+
+    // Returns an ErrorTarget for another writer of the same logger.
+    pub fn with_writer(&self, writer: &str) -> ErrorTarget {
+        ErrorTarget::new(
+            Arc::clone(&self.handler),
+            Arc::clone(&self.counters),
+            writer,
+        )
+    }
+
+    pub fn write_failed(&self, error: io::Error, o_record: Option<&Record>) {
+        match self.writer.as_str() {
+            "primary_writer" => increment(&self.counters.write_errors),
+            name => {
+                if let Some(writer_counters) = self.counters.writers.get(name) {
+                    increment(&writer_counters.write_errors);
+                }
+            }
+        }
+        self.handler.handle(
+            LoggingError::Write {
+                writer: self.writer.clone(),
+                error,
+            },
+            o_record,
+        );
+    }
+
+    pub fn flush_failed(&self, error: io::Error) {
+        match self.writer.as_str() {
+            "primary_writer" => increment(&self.counters.flush_errors),
+            name => {
+                if let Some(writer_counters) = self.counters.writers.get(name) {
+                    increment(&writer_counters.flush_errors);
+                }
+            }
+        }
+        self.handler.handle(
+            LoggingError::Flush {
+                writer: self.writer.clone(),
+                error,
+            },
+            None,
+        );
+    }
+}
+
+thread_local! {
+    // The ErrorTarget that is offered to an additional writer while the logger registers it.
+    static OFFERED_TARGET: RefCell<Option<ErrorTarget>> = RefCell::new(None);
+}
+
+// Offers the ErrorTarget to an additional writer, whose type the logger does not know,
+// by flushing it: a FileLogWriter connects its ErrorSink to the offered target when it is flushed.
+pub fn offer_target(writer: &dyn LogWriter, target: ErrorTarget) {
+    OFFERED_TARGET.with(|offered| *offered.borrow_mut() = Some(target.clone()));
+    let result = writer.flush();
+    OFFERED_TARGET.with(|offered| *offered.borrow_mut() = None);
+    if let Err(e) = result {
+        target.flush_failed(e);
+    }
+}
+
+// Takes the errors of a writer that do not surface in the results of its methods,
+// e.g. those of the background thread of a FileLogWriter in async mode.
+//
+// Once the writer is registered with a logger, the errors go to its ErrorTarget;
+// before, they are printed to stderr. Clones share the ErrorTarget.
+#[derive(Clone, Default)]
+pub struct ErrorSink(Arc<RwLock<Option<ErrorTarget>>>);
+
+impl ErrorSink {
+    pub fn connect(&self, target: ErrorTarget) {
+        *self.0.write().unwrap(/* ok */) = Some(target);
+    }
+
+    // Connects to the ErrorTarget that is offered to the writer, if any, see offer_target().
+    pub fn connect_offered(&self) {
+        if let Some(target) = OFFERED_TARGET.with(|offered| offered.borrow_mut().take()) {
+            self.connect(target);
+        }
+    }
+
+    pub fn write_failed(&self, error: io::Error) {
+        match self.target() {
+            Some(target) => target.write_failed(error, None),
+            None => eprintln!("FlexiLogger: writing log line failed with {}", error),
+        }
+    }
+
+    pub fn flush_failed(&self, error: io::Error) {
+        match self.target() {
+            Some(target) => target.flush_failed(error),
+            None => eprintln!("FlexiLogger: flushing failed with {}", error),
+        }
+    }
+
+    // the lock is not held while the error is handled
+    fn target(&self) -> Option<ErrorTarget> {
+        self.0.read().unwrap(/* ok */).clone()
+    }
+}
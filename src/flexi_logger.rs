@@ -1,27 +1,53 @@
+use deduplicator::Deduplicator;
+use error_handler::{offer_target, ErrorHandler, ErrorTarget, LoggingError};
+use limiter::Admission;
 use primary_writer::PrimaryWriter;
-use writers::LogWriter;
+use statistics::{increment, LoggerCounters, StatisticsHandle};
+use writers::{LogWriter, RingBufferWriter};
 use LogSpecification;
 
 use log;
-use regex::Regex;
 use std::collections::HashMap;
+use std::io;
 use std::sync::{Arc, RwLock};
 
 pub enum LogSpec {
     STATIC(LogSpecification),
     DYNAMIC(Arc<RwLock<LogSpecification>>),
 }
+impl LogSpec {
+    // Evaluates the given function with the current LogSpecification.
+    fn with<R, F: FnOnce(&LogSpecification) -> R>(&self, f: F) -> R {
+        match *self {
+            LogSpec::STATIC(ref ls) => f(ls),
+            LogSpec::DYNAMIC(ref locked_ls) => {
+                let guard = locked_ls.read();
+                f(guard.as_ref().unwrap(/* not sure if we should expose this */))
+            }
+        }
+    }
+}
 
 // Implements log::Log to plug into the log crate.
 //
 // Delegates the real logging to the configured PrimaryWriter and optionally to other writers.
-// The `PrimaryWriter` is either a `StdErrWriter` or an `ExtendedFileWriter`.
+// The `PrimaryWriter` is either a `StdErrWriter`, a `StdOutWriter`, or an `ExtendedFileWriter`.
 // An ExtendedFileWriter logs to a file, by delegating to a FileWriter,
-// and can additionally duplicate log lines to stderr.
+// and can additionally duplicate log lines to stderr and stdout.
+// Other writers can have their own LogSpec, which is then checked before they get a record.
+// The RingBufferWriter, if any, gets all records, before any filtering.
+// The Deduplicator, if any, suppresses repeated records for the PrimaryWriter.
+// The counters are shared with the StatisticsHandle.
+// Errors of the writers are passed to the ErrorHandler.
 pub struct FlexiLogger {
     log_specification: LogSpec,
     primary_writer: Arc<PrimaryWriter>,
-    other_writers: HashMap<String, Box<LogWriter>>,
+    other_writers: Arc<HashMap<String, Box<LogWriter>>>,
+    other_writer_specs: HashMap<String, LogSpec>,
+    o_ring_buffer: Option<RingBufferWriter>,
+    o_deduplicator: Option<Deduplicator>,
+    counters: Arc<LoggerCounters>,
+    error_handler: Arc<ErrorHandler>,
 }
 
 impl FlexiLogger {
@@ -29,33 +55,84 @@ impl FlexiLogger {
         log_specification: LogSpec,
         primary_writer: Arc<PrimaryWriter>,
         other_writers: HashMap<String, Box<LogWriter>>,
+        other_writer_specs: HashMap<String, LogSpec>,
+        o_ring_buffer: Option<RingBufferWriter>,
+        o_deduplicator: Option<Deduplicator>,
+        error_handler: ErrorHandler,
     ) -> FlexiLogger {
+        let counters = Arc::new(LoggerCounters::new(other_writers.keys()));
+        let error_handler = Arc::new(error_handler);
+
+        // the errors that writers have outside of write() and flush() are handled like the others
+        let error_target = |writer: &str| {
+            ErrorTarget::new(Arc::clone(&error_handler), Arc::clone(&counters), writer)
+        };
+        primary_writer.connect_errors(error_target("primary_writer"));
+        for (name, writer) in &other_writers {
+            offer_target(&**writer, error_target(name));
+        }
+        if let Some(ref ring_buffer) = o_ring_buffer {
+            ring_buffer.connect_errors(error_target("ring_buffer"));
+        }
+
         FlexiLogger {
             log_specification,
             primary_writer,
-            other_writers,
+            counters,
+            other_writers: Arc::new(other_writers),
+            other_writer_specs,
+            o_ring_buffer,
+            o_deduplicator,
+            error_handler,
         }
     }
-    // Implementation of Log::enabled() with easier testable signature
-    fn fl_enabled(&self, level: log::Level, target: &str) -> bool {
-        match self.log_specification {
-            LogSpec::STATIC(ref ls) => ls.enabled(level, target),
-            LogSpec::DYNAMIC(ref locked_ls) => {
-                let guard = locked_ls.read();
-                guard.as_ref()
-                    .unwrap(/* not sure if we should expose this */)
-                    .enabled(level, target)
-            }
-        }
+
+    pub fn statistics_handle(&self) -> StatisticsHandle {
+        StatisticsHandle::new(
+            Arc::clone(&self.counters),
+            Arc::clone(&self.primary_writer),
+            Arc::clone(&self.other_writers),
+        )
     }
-}
 
-impl log::Log for FlexiLogger {
-    fn enabled(&self, metadata: &log::Metadata) -> bool {
-        self.fl_enabled(metadata.level(), metadata.target())
+    // Passes a failed write to the error handler.
+    fn write_failed(&self, writer: &str, error: io::Error, o_record: Option<&log::Record>) {
+        self.error_handler.handle(
+            LoggingError::Write {
+                writer: writer.to_string(),
+                error,
+            },
+            o_record,
+        );
     }
 
-    fn log(&self, record: &log::Record) {
+    // Counts a failed write to the primary writer and passes it to the error handler.
+    fn primary_write_failed(&self, error: io::Error, o_record: Option<&log::Record>) {
+        increment(&self.counters.write_errors);
+        self.write_failed("primary_writer", error, o_record);
+    }
+
+    // Passes a failed flush to the error handler.
+    fn flush_failed(&self, writer: &str, error: io::Error) {
+        self.error_handler.handle(
+            LoggingError::Flush {
+                writer: writer.to_string(),
+                error,
+            },
+            None,
+        );
+    }
+
+    // Implementation of Log::log(); without check_spec, the log specification neither filters
+    // nor limits the record, which is used for logging panics.
+    // Returns false if the primary writer failed to write the record.
+    pub fn fl_log(&self, record: &log::Record, check_spec: bool) -> bool {
+        if let Some(ref ring_buffer) = self.o_ring_buffer {
+            ring_buffer
+                .write(record)
+                .unwrap_or_else(|e| self.write_failed("ring_buffer", e, None));
+        }
+
         let target = record.metadata().target();
         if target.starts_with('{') {
             let mut use_default = false;
@@ -65,64 +142,235 @@ impl log::Log for FlexiLogger {
                     use_default = true;
                 } else {
                     match self.other_writers.get(t) {
-                        None => eprintln!("bad writer spec: {}", t),
+                        None => {
+                            increment(&self.counters.bad_writer_names);
+                            self.error_handler
+                                .handle(LoggingError::UnknownWriter(t.to_string()), None);
+                        }
                         Some(writer) => {
-                            writer.write(record).unwrap_or_else(|e| {
-                                eprintln!(
-                                    "FlexiLogger: writing log line to custom_writer failed with {}",
-                                    e
-                                );
-                            });
+                            let counters = &self.counters.writers[t];
+                            if !self.other_writer_enabled(t, record) {
+                                increment(&counters.suppressed_by_spec);
+                                continue;
+                            }
+                            if !self.other_writer_admits(t, record) {
+                                increment(&counters.suppressed_by_limit);
+                                continue;
+                            }
+                            match writer.write(record) {
+                                Ok(()) => increment(&counters.records),
+                                Err(e) => {
+                                    increment(&counters.write_errors);
+                                    self.write_failed(t, e, Some(record));
+                                }
+                            }
                         }
                     }
                 }
             }
             if !use_default {
-                return;
+                return true;
             }
         }
 
-        if !self.enabled(record.metadata()) {
-            return;
+        if check_spec && !self.fl_enabled(record.level(), record.target()) {
+            increment(&self.counters.suppressed_by_spec);
+            return true;
         }
 
-        // closure that we need below
-        let check_text_filter = |text_filter: &Option<Regex>| {
-            if let Some(filter) = text_filter.as_ref() {
-                filter.is_match(&*record.args().to_string())
-            } else {
-                true
-            }
+        if check_spec
+            && !self
+                .log_specification
+                .with(|ls| text_filter_matches(ls, record))
+        {
+            increment(&self.counters.suppressed_by_text_filter);
+            return true;
+        }
+
+        if check_spec && !self.log_specification.with(|ls| {
+            limit_admits(
+                ls,
+                record.target(),
+                |r| self.primary_writer.write(r),
+                |e| self.primary_write_failed(e, None),
+            )
+        }) {
+            increment(&self.counters.suppressed_by_limit);
+            return true;
+        }
+
+        let result = match self.o_deduplicator {
+            Some(ref deduplicator) => deduplicator.write(record, |r| self.primary_writer.write(r)),
+            None => self.primary_writer.write(record).map(|()| true),
         };
+        match result {
+            Ok(true) => increment(self.counters.records(record.level())),
+            Ok(false) => increment(&self.counters.suppressed_as_duplicate),
+            Err(e) => {
+                self.primary_write_failed(e, Some(record));
+                return false;
+            }
+        }
+        true
+    }
 
-        if !match self.log_specification {
-            LogSpec::STATIC(ref ls) => check_text_filter(ls.text_filter()),
-            LogSpec::DYNAMIC(ref locked_ls) => {
-                let guard = locked_ls.read();
-                check_text_filter(
-                    guard.as_ref().unwrap(/* not sure if we should expose this */).text_filter(),
+    // Implementation of Log::enabled() with easier testable signature
+    fn fl_enabled(&self, level: log::Level, target: &str) -> bool {
+        self.log_specification.with(|ls| ls.enabled(level, target))
+    }
+
+    // Checks the record against the LogSpec of the other writer, if it has one.
+    // Since the target of such records denotes the writers, the module filters are
+    // evaluated against the module path.
+    fn other_writer_enabled(&self, writer_name: &str, record: &log::Record) -> bool {
+        match self.other_writer_specs.get(writer_name) {
+            None => true,
+            Some(log_spec) => log_spec.with(|ls| {
+                ls.enabled(
+                    record.level(),
+                    record.module_path().unwrap_or_else(|| record.target()),
+                ) && text_filter_matches(ls, record)
+            }),
+        }
+    }
+
+    // Applies the limit of the LogSpec of the other writer, if it has one.
+    fn other_writer_admits(&self, writer_name: &str, record: &log::Record) -> bool {
+        let writer = &self.other_writers[writer_name];
+        match self.other_writer_specs.get(writer_name) {
+            None => true,
+            Some(log_spec) => log_spec.with(|ls| {
+                limit_admits(
+                    ls,
+                    record.module_path().unwrap_or_else(|| record.target()),
+                    |r| writer.write(r),
+                    |e| {
+                        increment(&self.counters.writers[writer_name].write_errors);
+                        self.write_failed(writer_name, e, None);
+                    },
                 )
+            }),
+        }
+    }
+}
+
+// Applies the limit of the log specification for the module, if there is one,
+// and writes a note about dropped log lines, if one is due.
+fn limit_admits<F: Fn(&log::Record) -> io::Result<()>, E: Fn(io::Error)>(
+    log_spec: &LogSpecification,
+    module: &str,
+    write: F,
+    on_error: E,
+) -> bool {
+    match log_spec.limiter_for(module) {
+        None => true,
+        Some(limiter) => match limiter.admit() {
+            Admission::Pass => true,
+            Admission::PassWithNote(dropped) => {
+                limiter.write_note(dropped, write).unwrap_or_else(on_error);
+                true
             }
-        } {
-            return;
+            Admission::Drop => false,
+        },
+    }
+}
+
+// Writes notes about the log lines that were dropped due to the limits of the log specification
+// and were not yet noted.
+fn write_dropped_notes<F: Fn(&log::Record) -> io::Result<()>, E: Fn(io::Error)>(
+    log_spec: &LogSpecification,
+    write: F,
+    on_error: E,
+) {
+    for limiter in log_spec
+        .limiters()
+        .iter()
+        .chain(log_spec.retired_limiters())
+    {
+        let dropped = limiter.take_dropped();
+        if dropped > 0 {
+            limiter.write_note(dropped, &write).unwrap_or_else(&on_error);
         }
+    }
+}
 
-        self.primary_writer.write(record).unwrap_or_else(|e| {
-            eprintln!(
-                "FlexiLogger: writing log line to primary_writer failed with {}",
-                e
-            );
-        });
+fn text_filter_matches(log_spec: &LogSpecification, record: &log::Record) -> bool {
+    if log_spec.has_text_filters() {
+        log_spec.text_matches(&*record.args().to_string())
+    } else {
+        true
     }
+}
+
+impl log::Log for FlexiLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        if let Some(ref ring_buffer) = self.o_ring_buffer {
+            if metadata.level() <= ring_buffer.level_filter() {
+                return true;
+            }
+        }
+        self.fl_enabled(metadata.level(), metadata.target())
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.fl_log(record, true);
+    }
+
 
     fn flush(&self) {
+        self.log_specification.with(|ls| {
+            write_dropped_notes(
+                ls,
+                |r| self.primary_writer.write(r),
+                |e| self.primary_write_failed(e, None),
+            )
+        });
+        for (name, log_spec) in &self.other_writer_specs {
+            if let Some(writer) = self.other_writers.get(name) {
+                log_spec.with(|ls| {
+                    write_dropped_notes(
+                        ls,
+                        |r| writer.write(r),
+                        |e| {
+                            increment(&self.counters.writers[name].write_errors);
+                            self.write_failed(name, e, None);
+                        },
+                    )
+                });
+            }
+        }
+        if let Some(ref deduplicator) = self.o_deduplicator {
+            deduplicator
+                .flush(|r| self.primary_writer.write(r))
+                .unwrap_or_else(|e| self.primary_write_failed(e, None));
+        }
         self.primary_writer.flush().unwrap_or_else(|e| {
-            eprintln!("FlexiLogger: flushing primary_writer failed with {}", e);
+            increment(&self.counters.flush_errors);
+            self.flush_failed("primary_writer", e);
         });
-        for writer in self.other_writers.values() {
+        for (name, writer) in self.other_writers.iter() {
             writer.flush().unwrap_or_else(|e| {
-                eprintln!("FlexiLogger: flushing custom_writer failed with {}", e);
+                increment(&self.counters.writers[name].flush_errors);
+                self.flush_failed(name, e);
             });
         }
+        self.error_handler.flush();
+    }
+}
+
+// Shares the FlexiLogger between the log crate and the panic hook.
+pub struct SharedFlexiLogger(pub Arc<FlexiLogger>);
+
+impl log::Log for SharedFlexiLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.0.log(record)
+    }
+
+    fn flush(&self) {
+        self.0.flush()
     }
 }
@@ -66,3 +66,64 @@ pub fn with_thread(w: &mut io::Write, record: &Record) -> Result<(), io::Error>
         &record.args()
     )
 }
+
+/// A logline-formatter that produces log lines in JSON format, one JSON object per line, like
+/// <br>
+/// ```{"timestamp":"2016-01-13T15:25:01.640870+01:00","level":"INFO","target":"foo::bar","module_path":"foo::bar","file":"src/foo/bar.rs","line":26,"thread":"taskreader","message":"Task successfully read from conf.json"}```
+/// <br>
+/// i.e. with timestamp, level, target, module path, file location, thread name,
+/// and the message, where missing values are written as `null`.
+///
+/// This format is well suited for log files that are processed by log shippers.
+pub fn json_format(w: &mut io::Write, record: &Record) -> Result<(), io::Error> {
+    write!(
+        w,
+        "{{\"timestamp\":\"{}\",\"level\":\"{}\",\"target\":",
+        Local::now().format("%Y-%m-%dT%H:%M:%S%.6f%:z"),
+        record.level()
+    )?;
+    write_json_string(w, Some(record.target()))?;
+    w.write_all(b",\"module_path\":")?;
+    write_json_string(w, record.module_path())?;
+    w.write_all(b",\"file\":")?;
+    write_json_string(w, record.file())?;
+    match record.line() {
+        Some(line) => write!(w, ",\"line\":{}", line)?,
+        None => w.write_all(b",\"line\":null")?,
+    }
+    w.write_all(b",\"thread\":")?;
+    write_json_string(w, thread::current().name())?;
+    w.write_all(b",\"message\":")?;
+    write_json_string(w, Some(&record.args().to_string()))?;
+    w.write_all(b"}")
+}
+
+// Writes the value as a quoted and escaped JSON string, or null.
+fn write_json_string<W: io::Write + ?Sized>(w: &mut W, value: Option<&str>) -> io::Result<()> {
+    let value = match value {
+        Some(value) => value,
+        None => return w.write_all(b"null"),
+    };
+    w.write_all(b"\"")?;
+    let mut start = 0;
+    for (idx, c) in value.char_indices() {
+        let escaped: Option<&[u8]> = match c {
+            '"' => Some(b"\\\""),
+            '\\' => Some(b"\\\\"),
+            '\n' => Some(b"\\n"),
+            '\r' => Some(b"\\r"),
+            '\t' => Some(b"\\t"),
+            _ => None,
+        };
+        if escaped.is_some() || c < ' ' {
+            w.write_all(&value.as_bytes()[start..idx])?;
+            match escaped {
+                Some(escaped) => w.write_all(escaped)?,
+                None => write!(w, "\\u{:04x}", c as u32)?,
+            }
+            start = idx + c.len_utf8();
+        }
+    }
+    w.write_all(&value.as_bytes()[start..])?;
+    w.write_all(b"\"")
+}
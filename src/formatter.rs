@@ -0,0 +1,186 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::Local;
+use log::Record;
+use std::io;
+use std::sync::Arc;
+use std::thread;
+use {FlexiLoggerError, FormatFunction};
+
+// The timestamp format that is used if a pattern contains `{ts}` without a format.
+const DEFAULT_TS_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.6f %:z";
+
+// Formats log lines, either with a format function or with a compiled format pattern.
+#[derive(Clone)]
+pub enum Formatter {
+    Function(FormatFunction),
+    Pattern(Arc<Vec<PatternItem>>),
+}
+impl Formatter {
+    // Compiles a format pattern like "{ts:%H:%M:%S%.3f} {level:5} [{module}] {msg}".
+    //
+    // Supported fields are ts, level, module, target, file, line, thread, and msg;
+    // ts takes an optional chrono format, all other fields take an optional width,
+    // with an optional alignment (`<` or `>`) in front. `{{` and `}}` produce literal braces.
+    pub fn parse_pattern(pattern: &str) -> Result<Formatter, FlexiLoggerError> {
+        let parse_error = |reason: String| {
+            FlexiLoggerError::Parse(format!(
+                "invalid format pattern \"{}\": {}",
+                pattern, reason
+            ))
+        };
+
+        let mut items = Vec::<PatternItem>::new();
+        let mut literal = String::new();
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(parse_error("unmatched '}'".to_string())),
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => return Err(parse_error("unclosed '{'".to_string())),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        items.push(PatternItem::Literal(literal.clone()));
+                        literal.clear();
+                    }
+                    items.push(parse_placeholder(&placeholder).map_err(parse_error)?);
+                }
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            items.push(PatternItem::Literal(literal));
+        }
+        Ok(Formatter::Pattern(Arc::new(items)))
+    }
+
+    // Writes the log line, without line break.
+    pub fn format<W: io::Write>(&self, w: &mut W, record: &Record) -> io::Result<()> {
+        match *self {
+            Formatter::Function(f) => (f)(w, record),
+            Formatter::Pattern(ref items) => {
+                for item in items.iter() {
+                    item.write(w, record)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl From<FormatFunction> for Formatter {
+    fn from(f: FormatFunction) -> Formatter {
+        Formatter::Function(f)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Field {
+    Level,
+    Module,
+    Target,
+    File,
+    Line,
+    Thread,
+    Message,
+}
+
+#[derive(Clone, Copy)]
+pub enum Alignment {
+    Left,
+    Right,
+}
+
+pub enum PatternItem {
+    Literal(String),
+    Timestamp(String),
+    Field(Field, Option<(Alignment, usize)>),
+}
+impl PatternItem {
+    fn write<W: io::Write>(&self, w: &mut W, record: &Record) -> io::Result<()> {
+        match *self {
+            PatternItem::Literal(ref s) => w.write_all(s.as_bytes()),
+            PatternItem::Timestamp(ref ts_format) => {
+                write!(w, "{}", Local::now().format(ts_format))
+            }
+            PatternItem::Field(field, None) => write_field(w, field, record),
+            PatternItem::Field(field, Some((alignment, width))) => {
+                let mut buf = Vec::<u8>::with_capacity(width);
+                write_field(&mut buf, field, record)?;
+                let value = String::from_utf8_lossy(&buf);
+                match alignment {
+                    Alignment::Left => write!(w, "{:<1$}", value, width),
+                    Alignment::Right => write!(w, "{:>1$}", value, width),
+                }
+            }
+        }
+    }
+}
+
+fn write_field<W: io::Write>(w: &mut W, field: Field, record: &Record) -> io::Result<()> {
+    match field {
+        Field::Level => write!(w, "{}", record.level()),
+        Field::Module => w.write_all(record.module_path().unwrap_or("<unnamed>").as_bytes()),
+        Field::Target => w.write_all(record.target().as_bytes()),
+        Field::File => w.write_all(record.file().unwrap_or("<unnamed>").as_bytes()),
+        Field::Line => write!(w, "{}", record.line().unwrap_or(0)),
+        Field::Thread => w.write_all(thread::current().name().unwrap_or("<unnamed>").as_bytes()),
+        Field::Message => write!(w, "{}", record.args()),
+    }
+}
+
+fn parse_placeholder(placeholder: &str) -> Result<PatternItem, String> {
+    let (name, o_spec) = match placeholder.find(':') {
+        Some(idx) => (&placeholder[..idx], Some(&placeholder[idx + 1..])),
+        None => (placeholder, None),
+    };
+    let field = match name.trim() {
+        "ts" => {
+            let ts_format = o_spec.unwrap_or(DEFAULT_TS_FORMAT);
+            if StrftimeItems::new(ts_format).any(|item| matches!(item, Item::Error)) {
+                return Err(format!("invalid timestamp format \"{}\"", ts_format));
+            }
+            return Ok(PatternItem::Timestamp(ts_format.to_string()));
+        }
+        "level" => Field::Level,
+        "module" => Field::Module,
+        "target" => Field::Target,
+        "file" => Field::File,
+        "line" => Field::Line,
+        "thread" => Field::Thread,
+        "msg" => Field::Message,
+        _ => return Err(format!("unknown field \"{{{}}}\"", placeholder)),
+    };
+    let o_width = match o_spec {
+        None => None,
+        Some(spec) => Some(parse_width(spec)?),
+    };
+    Ok(PatternItem::Field(field, o_width))
+}
+
+fn parse_width(spec: &str) -> Result<(Alignment, usize), String> {
+    let (alignment, digits) = if let Some(digits) = spec.strip_prefix('<') {
+        (Alignment::Left, digits)
+    } else if let Some(digits) = spec.strip_prefix('>') {
+        (Alignment::Right, digits)
+    } else {
+        (Alignment::Left, spec)
+    };
+    digits
+        .parse::<usize>()
+        .map(|width| (alignment, width))
+        .map_err(|_| format!("invalid width \"{}\"", spec))
+}
@@ -4,7 +4,7 @@
 //!
 //! There are configuration options to e.g.
 //!
-//! * decide whether you want to write your logs to stderr or to a file,
+//! * decide whether you want to write your logs to stderr, to stdout, or to a file,
 //! * configure the path and the filenames of the log files,
 //! * use file rotation,
 //! * specify the line format for the log lines,
@@ -19,12 +19,17 @@
 //!
 //! See [the homepage](https://crates.io/crates/flexi_logger) for how to get started.
 
+extern crate atty;
 extern crate chrono;
 extern crate glob;
+#[macro_use]
+extern crate lazy_static;
 #[cfg_attr(feature = "specfile", macro_use)]
 extern crate log;
 extern crate regex;
 
+#[cfg(feature = "compress")]
+extern crate flate2;
 #[cfg(feature = "specfile")]
 extern crate notify;
 #[cfg(feature = "specfile")]
@@ -34,26 +39,48 @@ extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 
+#[cfg(feature = "specfile")]
+extern crate serde_json;
+#[cfg(feature = "specfile")]
+extern crate serde_yaml;
 #[cfg(feature = "specfile")]
 extern crate toml;
 
+mod colors;
+mod deduplicator;
+mod error_handler;
 mod flexi_error;
 mod flexi_logger;
 mod formats;
+mod formatter;
+mod limiter;
 mod log_specification;
 mod logger;
+#[cfg(feature = "specfile")]
+mod logger_config;
+mod panic_hook;
 mod primary_writer;
 mod reconfiguration_handle;
+mod statistics;
 
 pub mod writers;
 
 /// Re-exports from log crate
+pub use colors::Palette;
+pub use error_handler::{ErrorPolicy, LoggingError};
 pub use flexi_error::FlexiLoggerError;
 pub use formats::*;
+pub use limiter::ModuleLimit;
 pub use log::{Level, LevelFilter, Record};
 pub use log_specification::{LogSpecBuilder, LogSpecification};
 pub use logger::{Duplicate, Logger};
+#[cfg(feature = "specfile")]
+pub use logger_config::{FileConfig, LoggerConfig, WriterConfig};
 pub use reconfiguration_handle::ReconfigurationHandle;
+pub use statistics::{
+    FileStatistics, LevelCounts, Statistics, StatisticsHandle, WriterStatistics,
+};
+pub use writers::{Age, OverflowPolicy};
 
 use std::io;
 
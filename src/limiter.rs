@@ -0,0 +1,247 @@
+use log::{Level, Record};
+use std::fmt;
+use std::io;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use FlexiLoggerError;
+
+// The minimal interval between two notes about dropped log lines of the same module filter.
+const NOTE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Limits the number of log lines that are written for a module, by sampling,
+/// by a rate limit, or both.
+///
+/// A `ModuleLimit` is assigned to a module filter of a
+/// [`LogSpecification`](struct.LogSpecification.html) and applies to all log lines
+/// that pass this module filter.
+/// When log lines are dropped due to the limit, a note with the number of dropped log lines
+/// is written now and then (at most every ten seconds), and when the logger is flushed.
+/// When the log specification is replaced, unchanged limits keep their state,
+/// and log lines that were dropped due to removed or changed limits are still noted.
+///
+/// In the spec string and in specfiles, a limit is appended to the log level with `@`,
+/// and consists of `;`-separated settings:
+///
+/// * `sample=N`: only one of N log lines is written
+/// * `rate=R`: at most R log lines per second are written, with bursts of up to R log lines
+/// * `rate=R;burst=B`: at most R log lines per second are written, with bursts of up to B
+///   log lines
+///
+/// Examples: `"info, mycrate::hot = debug@sample=100"`,
+/// `"info, mycrate::hot = debug@rate=100;burst=500"`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ModuleLimit {
+    /// Only one of so many log lines is written.
+    pub sample: Option<u64>,
+    /// At most so many log lines are written per second.
+    pub rate: Option<u64>,
+    /// At most so many log lines are written in a burst;
+    /// only relevant with `rate`, which is also the default.
+    pub burst: Option<u64>,
+}
+
+impl ModuleLimit {
+    /// Creates a limit that lets only one of `n` log lines through.
+    pub fn sample(n: u64) -> ModuleLimit {
+        ModuleLimit {
+            sample: Some(n),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a limit that lets at most `rate` log lines per second through,
+    /// with bursts of up to `burst` log lines.
+    pub fn rate(rate: u64, burst: u64) -> ModuleLimit {
+        ModuleLimit {
+            rate: Some(rate),
+            burst: Some(burst),
+            ..Default::default()
+        }
+    }
+
+    /// Parses a limit like `"sample=10"` or `"rate=100;burst=500"`.
+    pub fn parse(s: &str) -> Result<ModuleLimit, FlexiLoggerError> {
+        let mut limit = ModuleLimit::default();
+        for setting in s.split(';').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            let mut parts = setting.splitn(2, '=');
+            let key = parts.next().unwrap_or_default().trim();
+            let value = match parts.next().map(|v| v.trim().parse::<u64>()) {
+                Some(Ok(value)) if value > 0 => value,
+                _ => return Err(parse_error(s)),
+            };
+            match key {
+                "sample" => limit.sample = Some(value),
+                "rate" => limit.rate = Some(value),
+                "burst" => limit.burst = Some(value),
+                _ => return Err(parse_error(s)),
+            }
+        }
+        if limit == ModuleLimit::default() {
+            return Err(parse_error(s));
+        }
+        Ok(limit)
+    }
+
+    fn burst(&self) -> u64 {
+        self.burst.or(self.rate).unwrap_or(0)
+    }
+}
+
+fn parse_error(s: &str) -> FlexiLoggerError {
+    FlexiLoggerError::Parse(format!("invalid limit: \"{}\"", s))
+}
+
+impl fmt::Display for ModuleLimit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut separator = "";
+        if let Some(sample) = self.sample {
+            write!(f, "sample={}", sample)?;
+            separator = ";";
+        }
+        if let Some(rate) = self.rate {
+            write!(f, "{}rate={}", separator, rate)?;
+            separator = ";";
+        }
+        if let Some(burst) = self.burst {
+            write!(f, "{}burst={}", separator, burst)?;
+        }
+        Ok(())
+    }
+}
+
+// The decision of a Limiter about a log line.
+pub enum Admission {
+    // The log line is to be written.
+    Pass,
+    // The log line is to be written, after a note about the given number of dropped log lines.
+    PassWithNote(usize),
+    // The log line is to be dropped.
+    Drop,
+}
+
+// Applies a ModuleLimit to the log lines of a module filter.
+#[derive(Debug)]
+pub struct Limiter {
+    module_name: Option<String>,
+    limit: ModuleLimit,
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    count: u64,
+    tokens: f64,
+    last_refill: Instant,
+    dropped: usize,
+    last_note: Instant,
+}
+
+impl Limiter {
+    pub fn new(module_name: Option<String>, limit: ModuleLimit) -> Limiter {
+        let now = Instant::now();
+        Limiter {
+            module_name,
+            limit,
+            state: Mutex::new(LimiterState {
+                count: 0,
+                tokens: limit.burst() as f64,
+                last_refill: now,
+                dropped: 0,
+                last_note: now,
+            }),
+        }
+    }
+
+    pub fn module_name(&self) -> &Option<String> {
+        &self.module_name
+    }
+
+    pub fn limit(&self) -> ModuleLimit {
+        self.limit
+    }
+
+    pub fn admit(&self) -> Admission {
+        let mut state = self.state.lock().unwrap(/* ok */);
+        if !state.admit(&self.limit) {
+            state.dropped += 1;
+            return Admission::Drop;
+        }
+        if state.dropped > 0 && state.last_note.elapsed() >= NOTE_INTERVAL {
+            state.last_note = Instant::now();
+            let dropped = state.dropped;
+            state.dropped = 0;
+            Admission::PassWithNote(dropped)
+        } else {
+            Admission::Pass
+        }
+    }
+
+    // Returns true if log lines were dropped that were not yet noted.
+    pub fn has_dropped(&self) -> bool {
+        self.state.lock().unwrap(/* ok */).dropped > 0
+    }
+
+    // Returns and resets the number of dropped log lines that were not yet noted.
+    pub fn take_dropped(&self) -> usize {
+        let mut state = self.state.lock().unwrap(/* ok */);
+        state.last_note = Instant::now();
+        let dropped = state.dropped;
+        state.dropped = 0;
+        dropped
+    }
+
+    // Writes a note about dropped log lines with the given function.
+    pub fn write_note<F: Fn(&Record) -> io::Result<()>>(
+        &self,
+        dropped: usize,
+        write: F,
+    ) -> io::Result<()> {
+        let module = match self.module_name {
+            Some(ref module_name) => module_name.as_str(),
+            None => "the default",
+        };
+        write(
+            &Record::builder()
+                .args(format_args!(
+                    "{} log lines were dropped due to the limit for {} ({})",
+                    dropped, module, self.limit
+                ))
+                .level(Level::Warn)
+                .target("flexi_logger")
+                .module_path(self.module_name.as_deref())
+                .build(),
+        )
+    }
+}
+
+// Each clone starts with a fresh state.
+impl Clone for Limiter {
+    fn clone(&self) -> Limiter {
+        Limiter::new(self.module_name.clone(), self.limit)
+    }
+}
+
+impl LimiterState {
+    fn admit(&mut self, limit: &ModuleLimit) -> bool {
+        if let Some(sample) = limit.sample {
+            // count is the number of log lines since the last one that was let through
+            let pass = self.count == 0;
+            self.count = (self.count + 1) % sample;
+            if !pass {
+                return false;
+            }
+        }
+        if let Some(rate) = limit.rate {
+            let now = Instant::now();
+            let elapsed = now.duration_since(self.last_refill);
+            self.last_refill = now;
+            self.tokens =
+                (self.tokens + elapsed.as_secs_f64() * rate as f64).min(limit.burst() as f64);
+            if self.tokens < 1.0 {
+                return false;
+            }
+            self.tokens -= 1.0;
+        }
+        true
+    }
+}
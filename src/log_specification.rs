@@ -1,6 +1,7 @@
 use flexi_error::FlexiLoggerError;
+use limiter::{Limiter, ModuleLimit};
 use log;
-use regex::Regex;
+use regex::{self, Regex};
 #[cfg(feature = "specfile")]
 use std::collections::BTreeMap;
 use std::collections::HashMap;
@@ -12,9 +13,16 @@ use std::fs;
 #[cfg(feature = "specfile")]
 use std::io::Read;
 use std::io::Write;
+use std::mem;
 #[cfg(feature = "specfile")]
 use std::path::{Path, PathBuf};
 #[cfg(feature = "specfile")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "specfile")]
+use serde_json;
+#[cfg(feature = "specfile")]
+use serde_yaml;
+#[cfg(feature = "specfile")]
 use toml;
 use LevelFilter;
 
@@ -30,9 +38,11 @@ use LevelFilter;
 /// described with some Backus-Naur-form:
 ///
 /// ```text
-/// <log_level_spec> ::= single_log_level_spec[{,single_log_level_spec}][/<text_filter>]
-/// <single_log_level_spec> ::= <path_to_module>|<log_level>|<path_to_module>=<log_level>
-/// <text_filter> ::= <regex>
+/// <log_level_spec> ::= single_log_level_spec[{,single_log_level_spec}][{/<text_filter>}]
+/// <single_log_level_spec> ::= <level_spec>[@<limit>]
+/// <level_spec> ::= <path_to_module>|<log_level>|<path_to_module>=<log_level>
+/// <limit> ::= see ModuleLimit
+/// <text_filter> ::= <regex>|!<regex>
 /// ```
 ///
 /// * Examples:
@@ -57,7 +67,44 @@ use LevelFilter;
 ///   * `foobaz` (!)
 ///   * `foobaz::bar` (!)
 ///
-/// The optional text filter is applied for all modules.
+/// * A module name can contain the wildcard `*`, which matches any sequence of characters,
+///   including `::`. Such a module filter affects all modules whose name matches the pattern,
+///   and their submodules.<br>
+///   Example: ```"*::db"``` affects e.g.
+///
+///   * `crate1::db`
+///   * `crate2::db::pool`
+///   * `crate2::mod_a::db`
+///
+/// * A module name that starts with `^` is a regular expression, which is matched against the
+///   beginning of the module path. In the spec string, it cannot contain `,`, `=`, or `/`.<br>
+///   Example: ```"^crate[12]::db$"``` affects `crate1::db` and `crate2::db`,
+///   but not `crate1::db::pool`.
+///
+/// * If several module filters affect a module, the one that matches the longest part of the
+///   module path wins, which, for module filters without wildcards and regular expressions,
+///   is simply the longest one. If a module filter without wildcards and a module filter with
+///   wildcard or regular expression match equally long parts, the one without wildcards wins.<br>
+///   Example: with `"info, crate1 = warn, *::db = debug, crate1::db = trace"`,
+///   `crate1::mod_a` logs warnings, `crate2::db` logs debug messages,
+///   and `crate1::db` is fully traced.
+///
+/// * A module filter can have a [limit](struct.ModuleLimit.html), which reduces the number
+///   of log lines of the affected modules by sampling or by a rate limit.<br>
+///   Example: `"info, mycrate::hot = debug@rate=100;burst=500"`
+///
+/// The optional text filters are applied for all modules.
+/// A message is only written if it matches at least one of the text filters without `!`
+/// (if there are any), and none of the text filters with `!`.
+///
+/// * Examples:
+///
+///   * `"info/Hello"`: only messages that contain `Hello` are written
+///   * `"info/!heartbeat"`: messages that contain `heartbeat` are suppressed
+///   * `"info/foo/bar/!foobar"`: messages that contain `foo` or `bar`, but not `foobar`,
+///     are written
+///
+/// A text filter that is to match a leading `!` literally can be written as `[!]...`.
 ///
 /// Note that external module names are to be specified like in ```"extern crate ..."```, i.e.,
 /// for crates with a dash in their name this means: the dash is to be replaced with
@@ -65,7 +112,16 @@ use LevelFilter;
 #[derive(Clone, Debug)]
 pub struct LogSpecification {
     module_filters: Vec<ModuleFilter>,
+    module_patterns: Vec<ModulePattern>,
+    limiters: Vec<Limiter>,
+    // limiters of replaced specifications, whose dropped log lines were not yet noted
+    retired_limiters: Vec<Limiter>,
+    // the text filters as they are used for filtering
+    textfilters: Vec<Regex>,
+    exclude_textfilters: Vec<Regex>,
+    // the text filters combined into single regular expressions, for the accessors
     textfilter: Option<Regex>,
+    exclude_textfilter: Option<Regex>,
 }
 
 /// Defines which loglevel filter to use for a given module (or as default, if no module is given).
@@ -75,38 +131,205 @@ pub struct ModuleFilter {
     pub level_filter: LevelFilter,
 }
 
+// A module filter with wildcards or a regular expression, compiled for matching.
+#[derive(Clone, Debug)]
+struct ModulePattern {
+    regex: Regex,
+    // the index of the module filter
+    index: usize,
+}
+
+impl ModulePattern {
+    fn new(module_name: &str, index: usize) -> Option<ModulePattern> {
+        let pattern = if module_name.starts_with('^') {
+            format!("({})", module_name)
+        } else if module_name.contains('*') {
+            let parts: Vec<String> = module_name.split('*').map(regex::escape).collect();
+            format!("^({})(?:$|::)", parts.join(".*"))
+        } else {
+            return None;
+        };
+        match Regex::new(&pattern) {
+            Ok(regex) => Some(ModulePattern { regex, index }),
+            Err(e) => {
+                println!("warning: invalid module filter '{}' - {}", module_name, e);
+                None
+            }
+        }
+    }
+
+    // Returns the length of the matched part of the module path, if the module path matches.
+    fn match_len(&self, module_path: &str) -> Option<usize> {
+        self.regex
+            .captures(module_path)
+            .and_then(|captures| captures.get(1))
+            .map(|m| m.end())
+    }
+}
+
 impl LogSpecification {
+    // Sorts the module filters, compiles those with wildcards and regular expressions,
+    // and creates the limiters for those with limits.
+    fn new(
+        module_filters: Vec<ModuleFilter>,
+        limits: HashMap<Option<String>, ModuleLimit>,
+        textfilters: Vec<Regex>,
+        exclude_textfilters: Vec<Regex>,
+    ) -> LogSpecification {
+        let module_filters = module_filters.level_sort();
+        let module_patterns = module_filters
+            .iter()
+            .enumerate()
+            .filter_map(|(index, mf)| {
+                mf.module_name
+                    .as_ref()
+                    .and_then(|module_name| ModulePattern::new(module_name, index))
+            })
+            .collect();
+        let limiters = module_filters
+            .iter()
+            .filter_map(|mf| {
+                limits
+                    .get(&mf.module_name)
+                    .map(|limit| Limiter::new(mf.module_name.clone(), *limit))
+            })
+            .collect();
+        LogSpecification {
+            module_filters,
+            module_patterns,
+            limiters,
+            retired_limiters: Vec::new(),
+            textfilter: combine_text_filters(&textfilters),
+            exclude_textfilter: combine_text_filters(&exclude_textfilters),
+            textfilters,
+            exclude_textfilters,
+        }
+    }
+
     #[doc(hidden)]
     pub fn reconfigure(&mut self, other_spec: LogSpecification) {
         self.module_filters = other_spec.module_filters;
+        self.module_patterns = other_spec.module_patterns;
+        // limiters with an unchanged limit keep their state, the others are retired until
+        // their dropped log lines are noted
+        let mut old_limiters = mem::take(&mut self.limiters);
+        old_limiters.append(&mut self.retired_limiters);
+        self.limiters = other_spec
+            .limiters
+            .into_iter()
+            .map(|limiter| {
+                match old_limiters.iter().position(|old| {
+                    old.module_name() == limiter.module_name() && old.limit() == limiter.limit()
+                }) {
+                    Some(index) => old_limiters.swap_remove(index),
+                    None => limiter,
+                }
+            })
+            .collect();
+        self.retired_limiters = old_limiters
+            .into_iter()
+            .filter(|limiter| limiter.has_dropped())
+            .collect();
+        self.textfilters = other_spec.textfilters;
+        self.exclude_textfilters = other_spec.exclude_textfilters;
         self.textfilter = other_spec.textfilter;
+        self.exclude_textfilter = other_spec.exclude_textfilter;
     }
 
     /// Implementation of Log::enabled() with easier testable signature
     pub fn enabled(&self, level: log::Level, target_module: &str) -> bool {
+        match self.module_filter_for(target_module) {
+            Some(module_filter) => level <= module_filter.level_filter,
+            None => false,
+        }
+    }
+
+    // Finds the module filter that applies to the module, if any.
+    fn module_filter_for(&self, target_module: &str) -> Option<&ModuleFilter> {
+        // Search for the pattern that matches the longest part of the module path;
+        // for equally long matches, the first one wins.
+        let mut o_pattern_match: Option<(usize, &ModuleFilter)> = None;
+        for pattern in &self.module_patterns {
+            if let Some(len) = pattern.match_len(target_module) {
+                match o_pattern_match {
+                    Some((best_len, _)) if best_len >= len => {}
+                    _ => o_pattern_match = Some((len, &self.module_filters[pattern.index])),
+                }
+            }
+        }
+
         // Search for the longest match, the vector is assumed to be pre-sorted.
+        // The names of patterns never match here, since module paths don't contain
+        // '*' and don't start with '^'.
         for module_filter in &self.module_filters {
             match module_filter.module_name {
                 Some(ref module_name) if !target_module.starts_with(&**module_name) => {}
-                Some(..) | None => return level <= module_filter.level_filter,
+                Some(ref module_name) => {
+                    return match o_pattern_match {
+                        Some((len, pattern_filter)) if len > module_name.len() => {
+                            Some(pattern_filter)
+                        }
+                        _ => Some(module_filter),
+                    }
+                }
+                None => {
+                    return match o_pattern_match {
+                        Some((_, pattern_filter)) => Some(pattern_filter),
+                        None => Some(module_filter),
+                    }
+                }
             }
         }
-        false
+        o_pattern_match.map(|(_, pattern_filter)| pattern_filter)
+    }
+
+    // Returns the limiter of the module filter that applies to the module, if it has one.
+    pub(crate) fn limiter_for(&self, target_module: &str) -> Option<&Limiter> {
+        if self.limiters.is_empty() {
+            return None;
+        }
+        let module_filter = self.module_filter_for(target_module)?;
+        self.limiters
+            .iter()
+            .find(|limiter| *limiter.module_name() == module_filter.module_name)
+    }
+
+    // The limiters of the module filters.
+    pub(crate) fn limiters(&self) -> &[Limiter] {
+        &self.limiters
+    }
+
+    // The limiters that were replaced by reconfigure() and still have dropped log lines
+    // to note.
+    pub(crate) fn retired_limiters(&self) -> &[Limiter] {
+        &self.retired_limiters
+    }
+
+    /// Returns the limit of the module filter for the given module name
+    /// (or of the default module filter, if no module name is given), if it has one.
+    pub fn module_limit(&self, module_name: Option<&str>) -> Option<ModuleLimit> {
+        self.limiters
+            .iter()
+            .find(|limiter| limiter.module_name().as_deref() == module_name)
+            .map(|limiter| limiter.limit())
     }
 
     /// Returns a log specification from a String.
     pub fn parse(spec: &str) -> LogSpecification {
         let mut dirs = Vec::<ModuleFilter>::new();
+        let mut limits = HashMap::<Option<String>, ModuleLimit>::new();
 
         let mut parts = spec.split('/');
         let mods = parts.next();
-        let filter = parts.next();
-        if parts.next().is_some() {
-            println!(
-                "warning: invalid logging spec '{}', ignoring it (too many '/'s)",
-                spec
-            );
-            return LogSpecification::default(LevelFilter::Off).finalize();
+        let mut include_patterns = Vec::<&str>::new();
+        let mut exclude_patterns = Vec::<&str>::new();
+        for pattern in parts.filter(|p| !p.is_empty()) {
+            let mut chars = pattern.chars();
+            if chars.next() == Some('!') {
+                exclude_patterns.push(chars.as_str());
+            } else {
+                include_patterns.push(pattern);
+            }
         }
         if let Some(m) = mods {
             for s in m.split(',') {
@@ -114,6 +337,16 @@ impl LogSpecification {
                 if s.is_empty() {
                     continue;
                 }
+                let (s, o_limit) = match s.find('@') {
+                    None => (s, None),
+                    Some(pos) => match ModuleLimit::parse(&s[pos + 1..]) {
+                        Ok(limit) => (s[..pos].trim(), Some(limit)),
+                        Err(e) => {
+                            println!("warning: {} in logging spec '{}', ignoring it", e, s);
+                            (s[..pos].trim(), None)
+                        }
+                    },
+                };
                 let mut parts = s.split('=');
                 let (log_level, name) =
                     match (parts.next(), parts.next().map(|s| s.trim()), parts.next()) {
@@ -170,25 +403,23 @@ impl LogSpecification {
                             continue;
                         }
                     };
+                let module_name = name.map(|s| s.to_string());
+                if let Some(limit) = o_limit {
+                    limits.insert(module_name.clone(), limit);
+                }
                 dirs.push(ModuleFilter {
-                    module_name: name.map(|s| s.to_string()),
+                    module_name,
                     level_filter: log_level,
                 });
             }
         }
 
-        let textfilter = filter.and_then(|filter| match Regex::new(filter) {
-            Ok(re) => Some(re),
-            Err(e) => {
-                println!("warning: invalid regex filter - {}", e);
-                None
-            }
-        });
-
-        LogSpecification {
-            module_filters: dirs.level_sort(),
-            textfilter,
-        }
+        LogSpecification::new(
+            dirs,
+            limits,
+            text_filters_from_patterns(&include_patterns),
+            text_filters_from_patterns(&exclude_patterns),
+        )
     }
 
     /// Returns a log specification based on the value of the environment variable RUST_LOG,
@@ -211,18 +442,12 @@ impl LogSpecification {
 
     /// If the specfile does not exist, try to create it, with the current spec as content,
     /// under the specified name.
+    ///
+    /// The format of the file is determined by its suffix, see
+    /// [`file()`](#method.file).
     #[cfg(feature = "specfile")]
     pub fn ensure_specfile_is_valid(&self, specfile: &PathBuf) -> Result<(), FlexiLoggerError> {
-        if specfile
-            .extension()
-            .unwrap_or_else(|| OsStr::new(""))
-            .to_str()
-            .unwrap_or("") != "toml"
-        {
-            return Err(FlexiLoggerError::Parse(
-                "only files with suffix toml are supported".to_owned(),
-            ));
-        }
+        let format = SpecFileFormat::from_path(specfile)?;
 
         if Path::is_file(specfile) {
             return Ok(());
@@ -252,93 +477,92 @@ impl LogSpecification {
                 );
                 return Err(FlexiLoggerError::from(e));
             }
-            Ok(mut file) => {
-                self.to_toml(&mut file)?;
-            }
+            Ok(mut file) => match format {
+                SpecFileFormat::Toml => self.to_toml(&mut file)?,
+                SpecFileFormat::Json => self.to_json(&mut file)?,
+                SpecFileFormat::Yaml => self.to_yaml(&mut file)?,
+            },
         };
 
         Ok(())
     }
 
     /// Reads a log specification from a file.
+    ///
+    /// The format of the file is determined by its suffix: `toml`, `json`, or `yaml`
+    /// (or `yml`). All formats have the same structure, with the optional entries
+    /// `global_level` and `global_pattern`, and the optional section `modules`
+    /// with the levels per module.
     #[cfg(feature = "specfile")]
     pub fn file<P: AsRef<Path>>(specfile: P) -> Result<LogSpecification, FlexiLoggerError> {
+        let format = SpecFileFormat::from_path(specfile.as_ref())?;
+
         // Open the file in read-only mode.
         let mut file = fs::File::open(specfile)?;
 
-        // Read the content toml file as an instance of `LogSpecFileFormat`.
+        // Read the content of the file as an instance of `LogSpecFileFormat`.
         let mut s = String::new();
         file.read_to_string(&mut s)?;
-        LogSpecification::from_toml(&s)
+        LogSpecification::from_spec_file_format(format.deserialize(&s)?)
     }
 
-    //
     #[cfg(feature = "specfile")]
-    fn from_toml(s: &str) -> Result<LogSpecification, FlexiLoggerError> {
-        #[derive(Clone, Debug, Deserialize)]
-        struct LogSpecFileFormat {
-            pub global_level: Option<String>,
-            pub global_pattern: Option<String>,
-            pub modules: BTreeMap<String, String>,
-        }
-
-        let logspec_ff: LogSpecFileFormat = toml::from_str(s)?;
+    fn from_spec_file_format(
+        logspec_ff: LogSpecFileFormat,
+    ) -> Result<LogSpecification, FlexiLoggerError> {
         let mut module_filters = Vec::<ModuleFilter>::new();
-
-        if let Some(s) = logspec_ff.global_level {
-            module_filters.push(ModuleFilter {
-                module_name: None,
-                level_filter: parse_level_filter(s)?,
-            });
-        }
-
-        for (k, v) in logspec_ff.modules {
+        let mut limits = HashMap::<Option<String>, ModuleLimit>::new();
+
+        let global_level = logspec_ff.global_level.map(|level| (None, level));
+        let modules = logspec_ff
+            .modules
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(k, v)| (Some(k), v));
+        for (module_name, v) in global_level.into_iter().chain(modules) {
+            let (level_filter, o_limit) = parse_level_filter_and_limit(&v)?;
+            if let Some(limit) = o_limit {
+                limits.insert(module_name.clone(), limit);
+            }
             module_filters.push(ModuleFilter {
-                module_name: Some(k),
-                level_filter: parse_level_filter(v)?,
+                module_name,
+                level_filter,
             });
         }
 
-        let textfilter = match logspec_ff.global_pattern {
-            None => None,
-            Some(s) => match Regex::new(&s) {
-                Ok(re) => Some(re),
-                Err(e) => {
-                    println!("warning: invalid regex filter - {}", e);
-                    None
-                }
-            },
-        };
-
-        Ok(LogSpecification {
-            module_filters: module_filters.level_sort(),
-            textfilter,
-        })
+        Ok(LogSpecification::new(
+            module_filters,
+            limits,
+            logspec_ff
+                .global_pattern
+                .map(|p| text_filters_from_patterns(&p.into_vec()))
+                .unwrap_or_default(),
+            logspec_ff
+                .exclude_pattern
+                .map(|p| text_filters_from_patterns(&p.into_vec()))
+                .unwrap_or_default(),
+        ))
     }
 
     /// Serializes itself in toml format
     pub fn to_toml(&self, w: &mut Write) -> Result<(), FlexiLoggerError> {
         w.write_all(b"### Optional: Default log level\n")?;
-        let last = self.module_filters.last();
-        if last.is_some() && last.as_ref().unwrap().module_name.is_none() {
-            w.write_all(
-                format!(
-                    "global_level = '{}'\n",
-                    last.as_ref()
-                        .unwrap()
-                        .level_filter
-                        .to_string()
-                        .to_lowercase()
-                ).as_bytes(),
-            )?;
-        } else {
-            w.write_all(b"#global_level = 'info'\n")?;
+        match self.global_level_filter() {
+            Some(level_filter) => writeln!(w, "global_level = '{}'", level_filter)?,
+            None => w.write_all(b"#global_level = 'info'\n")?,
         }
 
         w.write_all(
             b"\n### Optional: specify a regular expression to suppress all messages that don't match\n",
         )?;
         w.write_all(b"#global_pattern = 'foo'\n")?;
+        w.write_all(b"### or a list of regular expressions, of which at least one must match\n")?;
+        w.write_all(b"#global_pattern = ['foo', 'bar']\n")?;
+
+        w.write_all(
+            b"\n### Optional: specify regular expressions to suppress all messages that match one\n",
+        )?;
+        w.write_all(b"#exclude_pattern = ['heartbeat', 'keep-alive']\n")?;
 
         w.write_all(
             b"\n### Specific log levels per module are optionally defined in this section\n",
@@ -348,14 +572,15 @@ impl LogSpecification {
             w.write_all(b"#'mod1' = 'warn'\n")?;
             w.write_all(b"#'mod2' = 'debug'\n")?;
             w.write_all(b"#'mod2::mod3' = 'trace'\n")?;
+            w.write_all(b"#'mod4' = 'debug@rate=100;burst=500'\n")?;
         }
         for mf in &self.module_filters {
             if mf.module_name.is_some() {
                 w.write_all(
                     format!(
-                        "'{}' = '{}'\n",
-                        mf.module_name.as_ref().unwrap(),
-                        mf.level_filter.to_string().to_lowercase()
+                        "{} = '{}'\n",
+                        toml_quoted(mf.module_name.as_ref().unwrap()),
+                        self.level_filter_and_limit(mf)
                     ).as_bytes(),
                 )?;
             }
@@ -363,6 +588,100 @@ impl LogSpecification {
         Ok(())
     }
 
+    /// Serializes itself in json format.
+    ///
+    /// Since json does not support comments, the file contains no explanations,
+    /// and only the entries that are defined.
+    #[cfg(feature = "specfile")]
+    pub fn to_json<W: Write>(&self, w: &mut W) -> Result<(), FlexiLoggerError> {
+        w.write_all(b"{\n")?;
+        if let Some(level_filter) = self.global_level_filter() {
+            writeln!(w, "  \"global_level\": \"{}\",", level_filter)?;
+        }
+        w.write_all(b"  \"modules\": {")?;
+        let mut separator = "\n";
+        for mf in &self.module_filters {
+            if let Some(ref module_name) = mf.module_name {
+                write!(
+                    w,
+                    "{}    {}: \"{}\"",
+                    separator,
+                    serde_json::to_string(module_name).unwrap(/* a string is always valid json */),
+                    self.level_filter_and_limit(mf)
+                )?;
+                separator = ",\n";
+            }
+        }
+        if separator == "\n" {
+            w.write_all(b"}\n")?;
+        } else {
+            w.write_all(b"\n  }\n")?;
+        }
+        w.write_all(b"}\n")?;
+        Ok(())
+    }
+
+    /// Serializes itself in yaml format.
+    pub fn to_yaml<W: Write>(&self, w: &mut W) -> Result<(), FlexiLoggerError> {
+        w.write_all(b"### Optional: Default log level\n")?;
+        match self.global_level_filter() {
+            Some(level_filter) => writeln!(w, "global_level: '{}'", level_filter)?,
+            None => w.write_all(b"#global_level: 'info'\n")?,
+        }
+
+        w.write_all(
+            b"\n### Optional: specify a regular expression to suppress all messages that don't match\n",
+        )?;
+        w.write_all(b"#global_pattern: 'foo'\n")?;
+        w.write_all(b"### or a list of regular expressions, of which at least one must match\n")?;
+        w.write_all(b"#global_pattern: ['foo', 'bar']\n")?;
+
+        w.write_all(
+            b"\n### Optional: specify regular expressions to suppress all messages that match one\n",
+        )?;
+        w.write_all(b"#exclude_pattern: ['heartbeat', 'keep-alive']\n")?;
+
+        w.write_all(
+            b"\n### Specific log levels per module are optionally defined in this section\n",
+        )?;
+        w.write_all(b"modules:\n")?;
+        if self.module_filters.is_empty() || self.module_filters[0].module_name.is_none() {
+            w.write_all(b"#  'mod1': 'warn'\n")?;
+            w.write_all(b"#  'mod2': 'debug'\n")?;
+            w.write_all(b"#  'mod2::mod3': 'trace'\n")?;
+            w.write_all(b"#  'mod4': 'debug@rate=100;burst=500'\n")?;
+        }
+        for mf in &self.module_filters {
+            if let Some(ref module_name) = mf.module_name {
+                writeln!(
+                    w,
+                    "  {}: '{}'",
+                    yaml_quoted(module_name),
+                    self.level_filter_and_limit(mf)
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    // The level filter that applies to all modules without specific level filter, if any,
+    // in lower case, with its limit, if it has one.
+    fn global_level_filter(&self) -> Option<String> {
+        match self.module_filters.last() {
+            Some(mf) if mf.module_name.is_none() => Some(self.level_filter_and_limit(mf)),
+            _ => None,
+        }
+    }
+
+    // The level filter of the module filter in lower case, with its limit, if it has one.
+    fn level_filter_and_limit(&self, mf: &ModuleFilter) -> String {
+        let level_filter = mf.level_filter.to_string().to_lowercase();
+        match self.module_limit(mf.module_name.as_deref()) {
+            Some(limit) => format!("{}@{}", level_filter, limit),
+            None => level_filter,
+        }
+    }
+
     /// Creates a LogSpecBuilder, setting the default log level.
     pub fn default(level_filter: LevelFilter) -> LogSpecBuilder {
         LogSpecBuilder::from_module_filters(&[ModuleFilter {
@@ -377,9 +696,116 @@ impl LogSpecification {
     }
 
     /// Provides a reference to the text filter.
+    ///
+    /// If several text filters were specified, they are combined into a single regular
+    /// expression that matches if one of them matches. It is built from their patterns,
+    /// so it does not reflect options that were set with a `RegexBuilder`;
+    /// the filtering itself uses the given regular expressions.
     pub fn text_filter(&self) -> &Option<Regex> {
         &(self.textfilter)
     }
+
+    /// Provides a reference to the exclusion text filter.
+    ///
+    /// If several exclusion text filters were specified, they are combined into a single
+    /// regular expression that matches if one of them matches,
+    /// see [`text_filter()`](#method.text_filter).
+    pub fn exclude_text_filter(&self) -> &Option<Regex> {
+        &(self.exclude_textfilter)
+    }
+
+    /// Returns true if the log specification has a text filter or an exclusion text filter.
+    pub fn has_text_filters(&self) -> bool {
+        !(self.textfilters.is_empty() && self.exclude_textfilters.is_empty())
+    }
+
+    /// Returns true if the text matches one of the text filters, if there are any,
+    /// and does not match any of the exclusion text filters.
+    pub fn text_matches(&self, text: &str) -> bool {
+        (self.textfilters.is_empty() || self.textfilters.iter().any(|re| re.is_match(text)))
+            && !self.exclude_textfilters.iter().any(|re| re.is_match(text))
+    }
+}
+
+// The file formats for log specification files and logger configuration files,
+// which are determined by the file suffix.
+#[cfg(feature = "specfile")]
+#[derive(Clone, Copy)]
+pub enum SpecFileFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+#[cfg(feature = "specfile")]
+impl SpecFileFormat {
+    pub fn from_path(specfile: &Path) -> Result<SpecFileFormat, FlexiLoggerError> {
+        match specfile
+            .extension()
+            .unwrap_or_else(|| OsStr::new(""))
+            .to_str()
+            .unwrap_or("")
+        {
+            "toml" => Ok(SpecFileFormat::Toml),
+            "json" => Ok(SpecFileFormat::Json),
+            "yaml" | "yml" => Ok(SpecFileFormat::Yaml),
+            _ => Err(FlexiLoggerError::Parse(
+                "only files with suffix toml, json, yaml, or yml are supported".to_owned(),
+            )),
+        }
+    }
+
+    pub fn deserialize<T: DeserializeOwned>(self, s: &str) -> Result<T, FlexiLoggerError> {
+        match self {
+            SpecFileFormat::Toml => Ok(toml::from_str(s)?),
+            SpecFileFormat::Json => serde_json::from_str(s)
+                .map_err(|e| FlexiLoggerError::Parse(format!("invalid json: {}", e))),
+            SpecFileFormat::Yaml => serde_yaml::from_str(s)
+                .map_err(|e| FlexiLoggerError::Parse(format!("invalid yaml: {}", e))),
+        }
+    }
+}
+
+// The structure of log specification files.
+#[cfg(feature = "specfile")]
+#[derive(Clone, Debug, Deserialize)]
+struct LogSpecFileFormat {
+    pub global_level: Option<String>,
+    pub global_pattern: Option<Patterns>,
+    pub exclude_pattern: Option<Patterns>,
+    pub modules: Option<BTreeMap<String, String>>,
+}
+
+// A single regular expression or a list of regular expressions in log specification files.
+#[cfg(feature = "specfile")]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum Patterns {
+    Single(String),
+    List(Vec<String>),
+}
+
+#[cfg(feature = "specfile")]
+impl Patterns {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Patterns::Single(s) => vec![s],
+            Patterns::List(v) => v,
+        }
+    }
+}
+
+// Parses a log level with an optional limit, like "debug@sample=10".
+#[cfg(feature = "specfile")]
+fn parse_level_filter_and_limit(
+    s: &str,
+) -> Result<(LevelFilter, Option<ModuleLimit>), FlexiLoggerError> {
+    match s.find('@') {
+        None => Ok((parse_level_filter(s)?, None)),
+        Some(pos) => Ok((
+            parse_level_filter(s[..pos].trim())?,
+            Some(ModuleLimit::parse(&s[pos + 1..])?),
+        )),
+    }
 }
 
 #[cfg(feature = "specfile")]
@@ -400,14 +826,74 @@ fn parse_level_filter<S: AsRef<str>>(s: S) -> Result<LevelFilter, FlexiLoggerErr
     })
 }
 
+// Quotes a module name for toml, as a literal string if possible, which cannot contain
+// single quotes, and otherwise as a basic string with escapes.
+fn toml_quoted(s: &str) -> String {
+    if !s.chars().any(|c| c == '\'' || c.is_control()) {
+        return format!("'{}'", s);
+    }
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Quotes a module name for yaml as a single-quoted scalar, in which single quotes are doubled.
+fn yaml_quoted(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+// Dashes are not allowed in module names, but in regular expressions.
 fn contains_dash(s: &str) -> bool {
-    s.find('-') != None
+    s.find('-') != None && !s.trim().starts_with('^')
+}
+
+// Compiles the valid ones of the given regular expressions; invalid ones are reported
+// and ignored.
+fn text_filters_from_patterns<S: AsRef<str>>(patterns: &[S]) -> Vec<Regex> {
+    patterns
+        .iter()
+        .filter_map(|p| match Regex::new(p.as_ref()) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                println!("warning: invalid regex filter - {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+// Combines the given regular expressions into a single regular expression
+// that matches if one of them matches.
+fn combine_text_filters(regexes: &[Regex]) -> Option<Regex> {
+    match regexes.len() {
+        0 => None,
+        1 => Some(regexes[0].clone()),
+        _ => {
+            let combined: Vec<String> = regexes
+                .iter()
+                .map(|re| format!("(?:{})", re.as_str()))
+                .collect();
+            Regex::new(&combined.join("|")).ok()
+        }
+    }
 }
 
 /// Builder for `LogSpecification`.
 #[derive(Clone, Default)]
 pub struct LogSpecBuilder {
     module_filters: HashMap<Option<String>, LevelFilter>,
+    limits: HashMap<Option<String>, ModuleLimit>,
+    include_filters: Vec<Regex>,
+    exclude_filters: Vec<Regex>,
 }
 
 impl LogSpecBuilder {
@@ -417,6 +903,7 @@ impl LogSpecBuilder {
         modfilmap.insert(None, LevelFilter::Off);
         LogSpecBuilder {
             module_filters: modfilmap,
+            ..Default::default()
         }
     }
 
@@ -428,6 +915,7 @@ impl LogSpecBuilder {
         }
         LogSpecBuilder {
             module_filters: modfilmap,
+            ..Default::default()
         }
     }
 
@@ -438,6 +926,9 @@ impl LogSpecBuilder {
     }
 
     /// Adds a log level filter, or updates the log level filter, for a module.
+    ///
+    /// The module name can contain wildcards or be a regular expression, see
+    /// [LogSpecification](struct.LogSpecification.html).
     pub fn module<M: AsRef<str>>(
         &mut self,
         module_name: M,
@@ -448,43 +939,76 @@ impl LogSpecBuilder {
         self
     }
 
+    /// Sets a limit for the default log level filter.
+    pub fn default_limit(&mut self, limit: ModuleLimit) -> &mut LogSpecBuilder {
+        self.limits.insert(None, limit);
+        self
+    }
+
+    /// Sets a limit for the log level filter of a module.
+    ///
+    /// The limit only has an effect if a log level filter is defined for the module.
+    pub fn limit<M: AsRef<str>>(
+        &mut self,
+        module_name: M,
+        limit: ModuleLimit,
+    ) -> &mut LogSpecBuilder {
+        self.limits
+            .insert(Some(module_name.as_ref().to_owned()), limit);
+        self
+    }
+
     /// Adds a log level filter, or updates the log level filter, for a module.
     pub fn remove<M: AsRef<str>>(&mut self, module_name: M) -> &mut LogSpecBuilder {
         self.module_filters
             .remove(&Some(module_name.as_ref().to_owned()));
+        self.limits.remove(&Some(module_name.as_ref().to_owned()));
+        self
+    }
+
+    /// Adds a text filter; only messages that match at least one of the added
+    /// text filters are written.
+    pub fn include_text(&mut self, tf: Regex) -> &mut LogSpecBuilder {
+        self.include_filters.push(tf);
+        self
+    }
+
+    /// Adds an exclusion text filter; messages that match one of the added
+    /// exclusion text filters are suppressed.
+    pub fn exclude_text(&mut self, tf: Regex) -> &mut LogSpecBuilder {
+        self.exclude_filters.push(tf);
         self
     }
 
-    /// Creates a log specification without text filter.
+    /// Creates a log specification with the added text filters.
     pub fn finalize(self) -> LogSpecification {
-        LogSpecification {
-            module_filters: self.module_filters.into_vec_module_filter(),
-            textfilter: None,
-        }
+        LogSpecification::new(
+            self.module_filters.into_vec_module_filter(),
+            self.limits,
+            self.include_filters,
+            self.exclude_filters,
+        )
     }
 
-    /// Creates a log specification with text filter.
-    pub fn finalize_with_textfilter(self, tf: Regex) -> LogSpecification {
-        LogSpecification {
-            module_filters: self.module_filters.into_vec_module_filter(),
-            textfilter: Some(tf),
-        }
+    /// Creates a log specification with the added text filters and the given text filter.
+    pub fn finalize_with_textfilter(mut self, tf: Regex) -> LogSpecification {
+        self.include_text(tf);
+        self.finalize()
     }
 
-    /// Creates a log specification without being consumed.
+    /// Creates a log specification with the added text filters without being consumed.
     pub fn build(&self) -> LogSpecification {
-        LogSpecification {
-            module_filters: self.module_filters.clone().into_vec_module_filter(),
-            textfilter: None,
-        }
+        self.clone().finalize()
     }
 
-    /// Creates a log specification without being consumed, optionally with a text filter.
+    /// Creates a log specification without being consumed, with the added text filters
+    /// and optionally with a further text filter.
     pub fn build_with_textfilter(&self, tf: Option<Regex>) -> LogSpecification {
-        LogSpecification {
-            module_filters: self.module_filters.clone().into_vec_module_filter(),
-            textfilter: tf,
+        let mut builder = self.clone();
+        if let Some(tf) = tf {
+            builder.include_text(tf);
         }
+        builder.finalize()
     }
 }
 
@@ -509,21 +1033,23 @@ trait LevelSort {
 impl LevelSort for Vec<ModuleFilter> {
     /// Sort the module filters by length of their name,
     /// this allows a little more efficient lookup at runtime.
+    /// Equally long names are sorted alphabetically, to make the order deterministic.
     fn level_sort(mut self) -> Vec<ModuleFilter> {
         self.sort_by(|a, b| {
             let alen = a.module_name.as_ref().map(|a| a.len()).unwrap_or(0);
             let blen = b.module_name.as_ref().map(|b| b.len()).unwrap_or(0);
-            blen.cmp(&alen)
+            blen.cmp(&alen).then_with(|| a.module_name.cmp(&b.module_name))
         });
         self
     }
 }
 
-#[cfg(features = "specfile")]
+#[cfg(feature = "specfile")]
 #[cfg(test)]
 mod tests {
     extern crate log;
     use log::{Level, LevelFilter};
+    use log_specification::SpecFileFormat;
     use {LogSpecBuilder, LogSpecification};
 
     #[test]
@@ -564,17 +1090,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn specfile_roundtrip() {
+        let spec = LogSpecification::parse(
+            "info@rate=100, mod1::mod2 = debug, ^it's::\"odd\"\\w+$ = trace@sample=10",
+        );
+        assert_eq!(
+            spec.module_filters()[0].module_name,
+            Some("^it's::\"odd\"\\w+$".to_string())
+        );
+        for format in &[
+            SpecFileFormat::Toml,
+            SpecFileFormat::Json,
+            SpecFileFormat::Yaml,
+        ] {
+            let mut buffer = Vec::<u8>::new();
+            match *format {
+                SpecFileFormat::Toml => spec.to_toml(&mut buffer),
+                SpecFileFormat::Json => spec.to_json(&mut buffer),
+                SpecFileFormat::Yaml => spec.to_yaml(&mut buffer),
+            }
+            .unwrap();
+            let s = String::from_utf8(buffer).unwrap();
+            let ls = LogSpecification::from_spec_file_format(format.deserialize(&s).unwrap())
+                .unwrap();
+
+            assert_eq!(ls.module_filters, spec.module_filters, "{}", s);
+            for mf in &spec.module_filters {
+                let module_name = mf.module_name.as_deref();
+                assert_eq!(
+                    ls.module_limit(module_name).map(|l| l.to_string()),
+                    spec.module_limit(module_name).map(|l| l.to_string())
+                );
+            }
+        }
+    }
+
     fn compare_specs(s1: &str, s2: &str) {
-        let ls1 = LogSpecification::from_toml(s1).unwrap();
+        let ls1 =
+            LogSpecification::from_spec_file_format(SpecFileFormat::Toml.deserialize(s1).unwrap())
+                .unwrap();
         let ls2 = LogSpecification::parse(s2);
 
         assert_eq!(ls1.module_filters, ls2.module_filters);
         assert_eq!(ls1.textfilter.is_none(), ls2.textfilter.is_none());
-        if ls1.textfilter.is_some() && ls2.textfilter.is_some() {
-            assert_eq!(
-                ls1.textfilter.unwrap().to_string(),
-                ls2.textfilter.unwrap().to_string()
-            );
+        if let (Some(tf1), Some(tf2)) = (ls1.textfilter, ls2.textfilter) {
+            assert_eq!(tf1.to_string(), tf2.to_string());
         }
     }
 
@@ -7,18 +7,26 @@ use std::sync::mpsc::channel;
 #[cfg(feature = "specfile")]
 use std::thread;
 #[cfg(feature = "specfile")]
-use std::time::Duration;
+use LoggerConfig;
 
-use flexi_logger::{FlexiLogger, LogSpec};
+use colors::Palette;
+use deduplicator::Deduplicator;
+use error_handler::{ErrorHandler, ErrorPolicy};
+use flexi_logger::{FlexiLogger, LogSpec, SharedFlexiLogger};
 use log;
-use primary_writer::PrimaryWriter;
+use panic_hook::install_panic_hook;
+use primary_writer::{PrimaryWriter, PrimaryWriterSettings};
 use reconfiguration_handle::reconfiguration_handle;
 use std::collections::HashMap;
+use std::mem;
 use std::sync::{Arc, RwLock};
-use writers::{FileLogWriter, FileLogWriterBuilder, LogWriter};
+use std::time::Duration;
+use writers::{
+    Age, FileLogWriter, FileLogWriterBuilder, LogWriter, OverflowPolicy, RingBufferWriter,
+};
 use FormatFunction;
 use ReconfigurationHandle;
-use {formats, FlexiLoggerError, LogSpecification};
+use {formats, FlexiLoggerError, LogSpecification, ModuleLimit};
 
 /// The entry-point for using `flexi_logger`.
 ///
@@ -56,10 +64,21 @@ use {formats, FlexiLoggerError, LogSpecification};
 pub struct Logger {
     spec: LogSpecification,
     log_to_file: bool,
+    log_to_stdout: bool,
     duplicate: Duplicate,
+    duplicate_stdout: Duplicate,
+    o_palette: Option<Palette>,
     format: FormatFunction,
+    o_format_pattern: Option<String>,
     flwb: FileLogWriterBuilder,
     other_writers: HashMap<String, Box<LogWriter>>,
+    other_writer_specs: HashMap<String, LogSpecification>,
+    o_ring_buffer: Option<RingBufferWriter>,
+    log_panics: bool,
+    panic_backtrace: bool,
+    o_dedup_window: Option<Duration>,
+    error_policy: ErrorPolicy,
+    o_error_limit: Option<ModuleLimit>,
 }
 
 /// Choose a way to create a Logger instance and define how to access the (initial)
@@ -71,10 +90,21 @@ impl Logger {
         Logger {
             spec: logspec,
             log_to_file: false,
+            log_to_stdout: false,
             duplicate: Duplicate::None,
+            duplicate_stdout: Duplicate::None,
+            o_palette: None,
             format: formats::default_format,
+            o_format_pattern: None,
             flwb: FileLogWriter::builder(),
             other_writers: HashMap::<String, Box<LogWriter>>::new(),
+            other_writer_specs: HashMap::<String, LogSpecification>::new(),
+            o_ring_buffer: None,
+            log_panics: false,
+            panic_backtrace: false,
+            o_dedup_window: None,
+            error_policy: ErrorPolicy::StdErr,
+            o_error_limit: Some(ModuleLimit::rate(1, 10)),
         }
     }
 
@@ -94,6 +124,52 @@ impl Logger {
     pub fn with_env_or_str<S: AsRef<str>>(s: S) -> Logger {
         Logger::with(LogSpecification::env_or_parse(s))
     }
+
+    /// Creates a Logger that is configured according to the given
+    /// [`LoggerConfig`](struct.LoggerConfig.html), e.g. from a configuration file.
+    ///
+    /// The additional writers of the configuration are instantiated here, so that errors
+    /// in their configuration are reported immediately.
+    ///
+    /// This method is only available with the `specfile` feature.
+    #[cfg(feature = "specfile")]
+    pub fn from_config(config: &LoggerConfig) -> Result<Logger, FlexiLoggerError> {
+        let spec = match config.spec {
+            Some(ref spec) => LogSpecification::parse(spec),
+            None => LogSpecification::env(),
+        };
+        let mut logger = Logger::with(spec)
+            .o_log_to_file(config.log_to_file)
+            .o_log_to_stdout(config.log_to_stdout)
+            .o_colored(config.colored)
+            .o_log_panics(config.log_panics);
+        if let Some(format) = config.format_function()? {
+            logger = logger.format(format);
+        }
+        logger = logger.o_format_pattern(config.format_pattern.clone());
+        logger = logger.o_deduplicate(config.deduplicate_window()?);
+        if let Some(duplicate) = config.duplicate_to_stderr_level()? {
+            logger = logger.duplicate_to_stderr(duplicate);
+        }
+        if let Some(duplicate) = config.duplicate_to_stdout_level()? {
+            logger = logger.duplicate_to_stdout(duplicate);
+        }
+        if let Some(ref palette) = config.palette {
+            logger = logger.palette(Palette::parse(palette)?);
+        }
+        logger.flwb = config.file.apply(logger.flwb)?;
+
+        for (name, writer_config) in &config.writers {
+            let writer = Box::new(writer_config.file_log_writer(name)?);
+            logger = match writer_config.spec {
+                Some(ref spec) => {
+                    logger.add_writer_with_spec(name.clone(), writer, LogSpecification::parse(spec))
+                }
+                None => logger.add_writer(name.clone(), writer),
+            };
+        }
+        Ok(logger)
+    }
 }
 
 /// Choose a way how to start logging.
@@ -107,21 +183,42 @@ impl Logger {
             .spec
             .module_filters()
             .iter()
+            .chain(
+                self.other_writer_specs
+                    .values()
+                    .flat_map(|spec| spec.module_filters()),
+            )
             .map(|d| d.level_filter)
+            .chain(self.o_ring_buffer.as_ref().map(|rb| rb.level_filter()))
             .max()
             .unwrap_or(log::LevelFilter::Off);
 
-        log::set_boxed_logger(Box::new(FlexiLogger::new(
+        let settings = self.primary_writer_settings();
+        let primary_writer = Arc::new(self.primary_writer(&settings)?);
+        if let Some(ref ring_buffer) = self.o_ring_buffer {
+            ring_buffer.set_primary_writer(Arc::clone(&primary_writer));
+        }
+        let other_writer_specs = self
+            .other_writer_specs
+            .into_iter()
+            .map(|(name, spec)| (name, LogSpec::STATIC(spec)))
+            .collect();
+        let flexi_logger = Arc::new(FlexiLogger::new(
             LogSpec::STATIC(self.spec),
-            Arc::new(if self.log_to_file {
-                self.flwb = self.flwb.format(self.format);
-                PrimaryWriter::file(self.duplicate, self.flwb.instantiate()?)
-            } else {
-                PrimaryWriter::stderr(self.format)
-            }),
+            primary_writer,
             self.other_writers,
-        )))?;
+            other_writer_specs,
+            self.o_ring_buffer,
+            self.o_dedup_window.map(Deduplicator::new),
+            ErrorHandler::new(self.error_policy, self.o_error_limit),
+        ));
+        let statistics_handle = flexi_logger.statistics_handle();
+        log::set_boxed_logger(Box::new(SharedFlexiLogger(Arc::clone(&flexi_logger))))?;
+        statistics_handle.make_current();
         log::set_max_level(max);
+        if self.log_panics {
+            install_panic_hook(flexi_logger, self.panic_backtrace);
+        }
         Ok(())
     }
 
@@ -194,25 +291,46 @@ impl Logger {
     /// can be seen by comparing lines 9 and 21.
     ///
     pub fn start_reconfigurable(mut self) -> Result<ReconfigurationHandle, FlexiLoggerError> {
+        let settings = self.primary_writer_settings();
+        let primary_writer = Arc::new(self.primary_writer(&settings)?);
+        if let Some(ref ring_buffer) = self.o_ring_buffer {
+            ring_buffer.set_primary_writer(Arc::clone(&primary_writer));
+        }
         let spec = Arc::new(RwLock::new(self.spec));
+        let other_writer_specs: HashMap<String, Arc<RwLock<LogSpecification>>> = self
+            .other_writer_specs
+            .into_iter()
+            .map(|(name, spec)| (name, Arc::new(RwLock::new(spec))))
+            .collect();
 
-        let primary_writer = Arc::new(if self.log_to_file {
-            self.flwb = self.flwb.format(self.format);
-            PrimaryWriter::file(self.duplicate, self.flwb.instantiate()?)
-        } else {
-            PrimaryWriter::stderr(self.format)
-        });
-
-        let flexi_logger = FlexiLogger::new(
+        let flexi_logger = Arc::new(FlexiLogger::new(
             LogSpec::DYNAMIC(Arc::clone(&spec)),
             Arc::clone(&primary_writer),
             self.other_writers,
-        );
+            other_writer_specs
+                .iter()
+                .map(|(name, spec)| (name.clone(), LogSpec::DYNAMIC(Arc::clone(spec))))
+                .collect(),
+            self.o_ring_buffer,
+            self.o_dedup_window.map(Deduplicator::new),
+            ErrorHandler::new(self.error_policy, self.o_error_limit),
+        ));
 
-        log::set_boxed_logger(Box::new(flexi_logger))?;
+        let statistics_handle = flexi_logger.statistics_handle();
+        log::set_boxed_logger(Box::new(SharedFlexiLogger(Arc::clone(&flexi_logger))))?;
+        statistics_handle.make_current();
         // no optimization possible, because the spec is dynamic, but max is not:
         log::set_max_level(log::LevelFilter::Trace);
-        Ok(reconfiguration_handle(spec, primary_writer))
+        if self.log_panics {
+            install_panic_hook(flexi_logger, self.panic_backtrace);
+        }
+        Ok(reconfiguration_handle(
+            spec,
+            primary_writer,
+            settings,
+            other_writer_specs,
+            statistics_handle,
+        ))
     }
 
     /// Consumes the Logger object and initializes `flexi_logger` in a way that
@@ -247,18 +365,28 @@ impl Logger {
     /// global_level = 'info'
     /// ### Optional: specify a regular expression to suppress all messages that don't match
     /// #global_pattern = 'foo'
+    /// ### or a list of regular expressions, of which at least one must match
+    /// #global_pattern = ['foo', 'bar']
+    ///
+    /// ### Optional: specify regular expressions to suppress all messages that match one
+    /// #exclude_pattern = ['heartbeat', 'keep-alive']
     ///
     /// ### Specific log levels per module are optionally defined in this section
     /// [modules]
     /// #'mod1' = 'warn'
     /// #'mod2' = 'debug'
     /// #'mod2::mod3' = 'trace'
+    /// #'mod4' = 'debug@rate=100;burst=500'
     /// ```
     ///
     /// You can subsequently edit and modify the file according to your needs,
     /// while the program is running, and it will immediately take your changes into account.
     ///
-    /// Currently only toml-files are supported, the file suffix thus must be `.toml`.
+    /// Besides toml-files, also json-files and yaml-files with the same structure are supported;
+    /// the format is determined by the file suffix, which must be `.toml`, `.json`,
+    /// `.yaml`, or `.yml`.
+    /// Since json does not support comments, an initial json-file only contains the entries
+    /// of the initial spec.
     ///
     /// The initial spec remains valid if the file cannot be read.
     ///
@@ -326,6 +454,33 @@ impl Logger {
 
         Ok(())
     }
+
+    // Instantiates the writer for the log lines that are not directed to one of
+    // the other writers.
+    fn primary_writer_settings(&mut self) -> PrimaryWriterSettings {
+        PrimaryWriterSettings {
+            log_to_stdout: self.log_to_stdout,
+            duplicate_stderr: mem::replace(&mut self.duplicate, Duplicate::None),
+            duplicate_stdout: mem::replace(&mut self.duplicate_stdout, Duplicate::None),
+            o_palette: self.o_palette,
+            format: self.format,
+            o_format_pattern: self.o_format_pattern.clone(),
+            flwb: mem::replace(&mut self.flwb, FileLogWriter::builder())
+                .format(self.format)
+                .o_format_pattern(self.o_format_pattern.take()),
+        }
+    }
+
+    fn primary_writer(
+        &self,
+        settings: &PrimaryWriterSettings,
+    ) -> Result<PrimaryWriter, FlexiLoggerError> {
+        if self.log_to_file {
+            settings.file_writer(settings.flwb.clone())
+        } else {
+            settings.stream_writer()
+        }
+    }
 }
 
 /// Simple methods for influencing the behavior of the Logger.
@@ -336,6 +491,16 @@ impl Logger {
     ///  e.g. `myprog_2015-07-08_10-44-11.log`.
     pub fn log_to_file(mut self) -> Logger {
         self.log_to_file = true;
+        self.log_to_stdout = false;
+        self
+    }
+
+    /// Makes the logger write all logs to stdout, rather than to stderr.
+    ///
+    /// This is useful e.g. for programs that run in a container, where stdout is collected.
+    pub fn log_to_stdout(mut self) -> Logger {
+        self.log_to_stdout = true;
+        self.log_to_file = false;
         self
     }
 
@@ -352,6 +517,35 @@ impl Logger {
         self
     }
 
+    /// Makes the logger write messages with the specified minimum severity additionally to stdout.
+    ///
+    /// This option only has an effect if `log_to_file()` is used, too.
+    pub fn duplicate_to_stdout(mut self, dup: Duplicate) -> Logger {
+        self.duplicate_stdout = dup;
+        self
+    }
+
+    /// Makes the logger color the log lines it writes to stderr or stdout, depending on their
+    /// level, using the default [`Palette`](struct.Palette.html).
+    ///
+    /// This applies to the log lines that are written to stderr or stdout directly, and to the
+    /// duplicates of log lines that are written to a file; the file output is never colored.
+    ///
+    /// Colors are not used on a stream that is not a terminal, or if the environment variable
+    /// `NO_COLOR` is set.
+    pub fn colored(mut self) -> Logger {
+        self.o_palette = Some(Palette::default());
+        self
+    }
+
+    /// Makes the logger color the log lines it writes to stderr or stdout with the given palette.
+    ///
+    /// See [`colored()`](#method.colored).
+    pub fn palette(mut self, palette: Palette) -> Logger {
+        self.o_palette = Some(palette);
+        self
+    }
+
     /// Makes the logger write all logged error messages additionally to stderr.
     #[deprecated(note = "use duplicate_to_stderr(dup: Duplicate)")]
     pub fn duplicate_error(mut self) -> Logger {
@@ -371,10 +565,42 @@ impl Logger {
     ///
     /// You can either choose between some predefined variants,
     /// ```default_format```, ```opt_format```, ```detailed_format```, ```with_thread```,
+    /// ```json_format```,
     /// or you create and use your own format function
     /// with the signature ```fn(&Record) -> String```.
+    ///
+    /// See also [`format_pattern()`](#method.format_pattern).
     pub fn format(mut self, format: FormatFunction) -> Logger {
         self.format = format;
+        self.o_format_pattern = None;
+        self
+    }
+
+    /// Makes the logger format the log entries according to the given pattern,
+    /// rather than with a format function.
+    ///
+    /// The pattern is plain text with fields in braces, like
+    /// `"{ts:%H:%M:%S%.3f} {level:5} [{module}] {file}:{line} {msg}"`.
+    /// The following fields are supported:
+    ///
+    /// * `{ts}` - the timestamp; a
+    ///   [chrono format](https://docs.rs/chrono/0.4/chrono/format/strftime/index.html)
+    ///   can be given after a colon, the default is `%Y-%m-%d %H:%M:%S%.6f %:z`,
+    /// * `{level}` - the log level,
+    /// * `{module}` - the module path,
+    /// * `{target}` - the target of the log call,
+    /// * `{file}` and `{line}` - the source code location,
+    /// * `{thread}` - the name of the thread,
+    /// * `{msg}` - the log message.
+    ///
+    /// All fields except `{ts}` take an optional minimal width after a colon, like `{level:5}`;
+    /// the value is left-aligned, unless the width is prefixed with `>`.
+    /// Use `{{` and `}}` for literal braces.
+    ///
+    /// The pattern is validated when the logger is started; a wrong pattern makes
+    /// the start fail with `FlexiLoggerError::Parse`.
+    pub fn format_pattern<S: Into<String>>(mut self, pattern: S) -> Logger {
+        self.o_format_pattern = Some(pattern.into());
         self
     }
 
@@ -419,6 +645,20 @@ impl Logger {
         self
     }
 
+    /// By default, the log file will grow indefinitely.
+    /// With this option, a new file is opened whenever the given `Age` is reached,
+    /// e.g. every hour or at the start of every day.
+    /// Also the filename pattern changes - instead of the timestamp,
+    /// the period is included into the filename.
+    ///
+    /// This option can be combined with `rotate_over_size()`.
+    ///
+    /// This option only has an effect if `log_to_file()` is used, too.
+    pub fn rotate_over_age(mut self, age: Age) -> Logger {
+        self.flwb = self.flwb.rotate_over_age(age);
+        self
+    }
+
     /// This option only has an effect if `log_to_file` is set to true.
     ///
     /// By default, if there is a rotate_over_size defined the count of backup file
@@ -431,6 +671,39 @@ impl Logger {
         self
     }
 
+    /// Makes the logger delete log files of this and former program runs that were
+    /// last modified longer ago than the given duration.
+    /// See [`FileLogWriterBuilder::max_file_age()`](writers/struct.FileLogWriterBuilder.html#method.max_file_age)
+    /// for details.
+    ///
+    /// This option only has an effect if `log_to_file()` is used, too.
+    pub fn max_file_age(mut self, max_file_age: Duration) -> Logger {
+        self.flwb = self.flwb.max_file_age(max_file_age);
+        self
+    }
+
+    /// Makes the logger delete the oldest log files of this and former program runs
+    /// when the log files together exceed the given number of bytes.
+    /// See [`FileLogWriterBuilder::max_total_size()`](writers/struct.FileLogWriterBuilder.html#method.max_total_size)
+    /// for details.
+    ///
+    /// This option only has an effect if `log_to_file()` is used, too.
+    pub fn max_total_size(mut self, max_total_size: usize) -> Logger {
+        self.flwb = self.flwb.max_total_size(max_total_size);
+        self
+    }
+
+    /// Makes the logger compress each log file with gzip when rotation switches to the next file.
+    ///
+    /// This option only has an effect if `log_to_file()` and rotation are used, too.
+    ///
+    /// This method is only available with the `compress` feature.
+    #[cfg(feature = "compress")]
+    pub fn compress(mut self) -> Logger {
+        self.flwb = self.flwb.compress();
+        self
+    }
+
     /// Makes the logger append to the specified output file, if it exists already;
     /// by default, the file would be truncated.
     ///
@@ -441,6 +714,20 @@ impl Logger {
         self
     }
 
+    /// Makes the logger write to the log file in a background thread;
+    /// the log lines are handed over through a queue with the given capacity.
+    /// See [`FileLogWriterBuilder::async_mode()`](writers/struct.FileLogWriterBuilder.html#method.async_mode)
+    /// for details.
+    ///
+    /// Call `log::logger().flush()` before your program exits, to make sure that
+    /// all pending log lines are written.
+    ///
+    /// This option only has an effect if `log_to_file()` is used, too.
+    pub fn async_mode(mut self, capacity: usize, overflow: OverflowPolicy) -> Logger {
+        self.flwb = self.flwb.async_mode(capacity, overflow);
+        self
+    }
+
     /// The specified String is added to the log file name after the program name.
     ///
     /// This option only has an effect if `log_to_file()` is used, too.
@@ -458,13 +745,154 @@ impl Logger {
         self
     }
 
+    /// Makes the logger reopen its log file when the file was renamed or deleted
+    /// by someone else, e.g. by `logrotate`; see
+    /// [`FileLogWriterBuilder::reopen_on_external_rotation()`](writers/struct.FileLogWriterBuilder.html#method.reopen_on_external_rotation).
+    ///
+    /// The file can also be reopened explicitly, with
+    /// [`ReconfigurationHandle::reopen_output_file()`](struct.ReconfigurationHandle.html#method.reopen_output_file).
+    ///
+    /// This option only has an effect if `log_to_file()` is used, too.
+    pub fn reopen_on_external_rotation(mut self) -> Logger {
+        self.flwb = self.flwb.reopen_on_external_rotation();
+        self
+    }
+
+    /// Makes the logger recover from failures of writing to its log file, e.g. when the disk
+    /// is full, by keeping up to `buffer_size` bytes of log lines in memory, and writing
+    /// further log lines to stderr, until writing succeeds again; see
+    /// [`FileLogWriterBuilder::recover_from_io_errors()`](writers/struct.FileLogWriterBuilder.html#method.recover_from_io_errors).
+    ///
+    /// The start of a failure is passed once to the [`ErrorPolicy`](enum.ErrorPolicy.html)
+    /// and counted in the `write_errors` of the [`Statistics`](struct.Statistics.html).
+    /// A removed log directory is only detected when the file is rotated or reopened,
+    /// e.g. with [`reopen_on_external_rotation()`](#method.reopen_on_external_rotation).
+    ///
+    /// This option only has an effect if `log_to_file()` is used, too.
+    pub fn recover_from_io_errors(mut self, buffer_size: usize) -> Logger {
+        self.flwb = self.flwb.recover_from_io_errors(buffer_size);
+        self
+    }
+
     /// Registers a LogWriter implementation under the given target name.
     ///
     /// The target name should not start with an underscore.
     ///
     /// See [the module documentation of `writers`](writers/index.html).
     pub fn add_writer<S: Into<String>>(mut self, name: S, writer: Box<LogWriter>) -> Logger {
-        self.other_writers.insert(name.into(), writer);
+        let name = name.into();
+        self.other_writer_specs.remove(&name);
+        self.other_writers.insert(name, writer);
+        self
+    }
+
+    /// Registers a [`RingBufferWriter`](writers/struct.RingBufferWriter.html) that gets all
+    /// log calls, independent of the log specification, and keeps the most recent log lines
+    /// in memory, for being dumped on request or when an error is logged.
+    ///
+    /// The dumps are written to the primary log output of the logger, unless the
+    /// `RingBufferWriter` was configured with a dump file.
+    pub fn ring_buffer(mut self, ring_buffer: RingBufferWriter) -> Logger {
+        self.o_ring_buffer = Some(ring_buffer);
+        self
+    }
+
+    /// Makes the logger install a panic hook that logs panics at error level,
+    /// with target `panic`, and then flushes all writers.
+    ///
+    /// The log line contains the thread name, the panic message, and the location
+    /// of the panic. It is logged like other log lines, e.g. it also reaches the
+    /// `RingBufferWriter` and is counted in the statistics, but the log specification
+    /// cannot suppress it.
+    /// The hook replaces the previous panic hook, so panics are no longer
+    /// printed directly to stderr; only if writing the log line fails,
+    /// the previous panic hook is called.
+    pub fn log_panics(mut self) -> Logger {
+        self.log_panics = true;
+        self
+    }
+
+    /// Like [`log_panics()`](#method.log_panics), but the log line also contains
+    /// a backtrace of the panicking thread.
+    pub fn log_panics_with_backtrace(mut self) -> Logger {
+        self.log_panics = true;
+        self.panic_backtrace = true;
+        self
+    }
+
+    /// Makes the logger suppress consecutive identical log lines in its primary output.
+    ///
+    /// Log lines are identical if they have the same level, module path, and message.
+    /// A run of identical log lines is shown as the first of them, and, when the run ends,
+    /// a summary line "last message repeated N times" with the number of suppressed log lines.
+    /// A run ends when a different log line comes, when the logger is flushed, or when an
+    /// identical log line comes after the given window has passed since the start of the run;
+    /// the latter is then written and starts a new run.
+    ///
+    /// The additional writers are not affected.
+    pub fn deduplicate(mut self, window: Duration) -> Logger {
+        self.o_dedup_window = Some(window);
+        self
+    }
+
+    /// Determines how the logger deals with errors of its writers, and with log calls
+    /// that address unknown writers; by default, errors are printed to stderr.
+    ///
+    /// This includes the errors of the background threads of `FileLogWriter`s in async mode,
+    /// and of dumps of the [`RingBufferWriter`](writers/struct.RingBufferWriter.html).
+    ///
+    /// The reports of errors are limited, see
+    /// [`limit_error_reports()`](#method.limit_error_reports).
+    ///
+    /// ```rust
+    /// use flexi_logger::{ErrorPolicy, Logger};
+    ///
+    /// Logger::with_str("info")
+    ///     .error_policy(ErrorPolicy::Callback(Box::new(|e| {
+    ///         // forward the error to the monitoring system
+    ///         println!("logging failed: {}", e);
+    ///     })))
+    ///     .start()
+    ///     .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+    /// ```
+    pub fn error_policy(mut self, policy: ErrorPolicy) -> Logger {
+        self.error_policy = policy;
+        self
+    }
+
+    /// Limits the number of errors that are reported by the
+    /// [`ErrorPolicy`](enum.ErrorPolicy.html), so that e.g. a broken disk
+    /// does not flood stderr.
+    ///
+    /// The number of errors that were not reported is reported now and then
+    /// (at most every ten seconds), and when the logger is flushed.
+    /// Log lines that are written to a fallback writer are not affected.
+    ///
+    /// By default, a burst of up to ten errors, and then one error per second is reported.
+    pub fn limit_error_reports(mut self, limit: ModuleLimit) -> Logger {
+        self.o_error_limit = Some(limit);
+        self
+    }
+
+    /// Registers a LogWriter implementation under the given target name,
+    /// together with a LogSpecification that is applied to the log lines for this writer.
+    ///
+    /// The module filters of the LogSpecification are evaluated against the module path
+    /// of the log calls, the text filter against the log messages.
+    /// The LogSpecification can be replaced later, if the logger is started with
+    /// [`start_reconfigurable()`](#method.start_reconfigurable), with
+    /// [`ReconfigurationHandle::set_new_writer_spec()`](struct.ReconfigurationHandle.html#method.set_new_writer_spec).
+    ///
+    /// See [the module documentation of `writers`](writers/index.html).
+    pub fn add_writer_with_spec<S: Into<String>>(
+        mut self,
+        name: S,
+        writer: Box<LogWriter>,
+        spec: LogSpecification,
+    ) -> Logger {
+        let name = name.into();
+        self.other_writer_specs.insert(name.clone(), spec);
+        self.other_writers.insert(name, writer);
         self
     }
 }
@@ -479,6 +907,43 @@ impl Logger {
         self
     }
 
+    /// With true, makes the logger write all logs to stdout, otherwise to stderr.
+    ///
+    /// This option has no effect if `log_to_file` is set to true.
+    pub fn o_log_to_stdout(mut self, log_to_stdout: bool) -> Logger {
+        self.log_to_stdout = log_to_stdout;
+        self
+    }
+
+    /// With true, makes the logger install a panic hook that logs panics,
+    /// see [`log_panics()`](#method.log_panics).
+    pub fn o_log_panics(mut self, log_panics: bool) -> Logger {
+        self.log_panics = log_panics;
+        self
+    }
+
+    /// With true, makes the logger install a panic hook that logs panics with a backtrace,
+    /// see [`log_panics_with_backtrace()`](#method.log_panics_with_backtrace).
+    pub fn o_log_panics_with_backtrace(mut self, log_panics_with_backtrace: bool) -> Logger {
+        self.log_panics = log_panics_with_backtrace;
+        self.panic_backtrace = log_panics_with_backtrace;
+        self
+    }
+
+    /// With Some(window), makes the logger suppress consecutive identical log lines,
+    /// see [`deduplicate()`](#method.deduplicate).
+    pub fn o_deduplicate(mut self, o_window: Option<Duration>) -> Logger {
+        self.o_dedup_window = o_window;
+        self
+    }
+
+    /// With None, makes the logger report all errors, otherwise limits the number of
+    /// reported errors, see [`limit_error_reports()`](#method.limit_error_reports).
+    pub fn o_limit_error_reports(mut self, o_limit: Option<ModuleLimit>) -> Logger {
+        self.o_error_limit = o_limit;
+        self
+    }
+
     /// With true, makes the logger print an info message to stdout, each time
     /// when a new file is used for log-output.
     pub fn o_print_message(mut self, print_message: bool) -> Logger {
@@ -486,6 +951,25 @@ impl Logger {
         self
     }
 
+    /// With Some, makes the logger format the log entries according to the given pattern,
+    /// see [`format_pattern()`](#method.format_pattern);
+    /// with None, the format function is used.
+    pub fn o_format_pattern<S: Into<String>>(mut self, pattern: Option<S>) -> Logger {
+        self.o_format_pattern = pattern.map(|p| p.into());
+        self
+    }
+
+    /// With true, makes the logger color the log lines it writes to stderr or stdout
+    /// with the default palette, see [`colored()`](#method.colored).
+    pub fn o_colored(mut self, colored: bool) -> Logger {
+        self.o_palette = if colored {
+            Some(Palette::default())
+        } else {
+            None
+        };
+        self
+    }
+
     /// With true, makes the logger write all logged error messages additionally to stderr;
     /// with false, no messages are duplicated.
     #[deprecated(note = "use duplicate_to_stderr(dup: Duplicate)")]
@@ -533,6 +1017,24 @@ impl Logger {
         self
     }
 
+    /// This option only has an effect if `log_to_file` is set to true.
+    ///
+    /// With Some, makes the logger delete log files that were last modified longer ago
+    /// than the given duration, see [`max_file_age()`](#method.max_file_age).
+    pub fn o_max_file_age(mut self, max_file_age: Option<Duration>) -> Logger {
+        self.flwb = self.flwb.o_max_file_age(max_file_age);
+        self
+    }
+
+    /// This option only has an effect if `log_to_file` is set to true.
+    ///
+    /// With Some, makes the logger delete the oldest log files when the log files together
+    /// exceed the given number of bytes, see [`max_total_size()`](#method.max_total_size).
+    pub fn o_max_total_size(mut self, max_total_size: Option<usize>) -> Logger {
+        self.flwb = self.flwb.o_max_total_size(max_total_size);
+        self
+    }
+
     /// This option only has an effect if `log_to_file` is set to true.
     ///
     /// By default, and with None, the log file will grow indefinitely.
@@ -548,6 +1050,17 @@ impl Logger {
         self
     }
 
+    /// This option only has an effect if `log_to_file` is set to true.
+    ///
+    /// By default, and with None, the log file will grow indefinitely.
+    /// If an `Age` is set, a new file is opened whenever the age is reached.
+    /// Also the filename pattern changes - instead of the timestamp the period
+    /// is included into the filename.
+    pub fn o_rotate_over_age(mut self, age: Option<Age>) -> Logger {
+        self.flwb = self.flwb.o_rotate_over_age(age);
+        self
+    }
+
     /// With true, makes the logger include a timestamp into the names of the log files.
     /// `true` is the default, but `rotate_over_size` sets it to `false`.
     /// With this method you can set it to `true` again.
@@ -558,6 +1071,18 @@ impl Logger {
         self
     }
 
+    /// This option only has an effect if `log_to_file` is set to true.
+    ///
+    /// With true, makes the logger compress each log file with gzip when rotation switches
+    /// to the next file.
+    ///
+    /// This method is only available with the `compress` feature.
+    #[cfg(feature = "compress")]
+    pub fn o_compress(mut self, compress: bool) -> Logger {
+        self.flwb = self.flwb.o_compress(compress);
+        self
+    }
+
     /// This option only has an effect if `log_to_file` is set to true.
     ///
     /// If append is set to true, makes the logger append to the specified output file, if it exists.
@@ -570,6 +1095,15 @@ impl Logger {
         self
     }
 
+    /// This option only has an effect if `log_to_file` is set to true.
+    ///
+    /// With `Some((capacity, overflow))`, makes the logger write to the log file
+    /// in a background thread.
+    pub fn o_async_mode(mut self, async_mode: Option<(usize, OverflowPolicy)>) -> Logger {
+        self.flwb = self.flwb.o_async_mode(async_mode);
+        self
+    }
+
     /// This option only has an effect if `log_to_file` is set to true.
     ///
     /// The specified String is added to the log file name.
@@ -586,9 +1120,31 @@ impl Logger {
         self.flwb = self.flwb.o_create_symlink(symlink);
         self
     }
+
+    /// This option only has an effect if `log_to_file` is set to true.
+    ///
+    /// With true, makes the logger reopen its log file when the file was renamed or deleted
+    /// by someone else, see [`reopen_on_external_rotation()`](#method.reopen_on_external_rotation).
+    pub fn o_reopen_on_external_rotation(mut self, reopen_on_external_rotation: bool) -> Logger {
+        self.flwb = self
+            .flwb
+            .o_reopen_on_external_rotation(reopen_on_external_rotation);
+        self
+    }
+
+    /// This option only has an effect if `log_to_file` is set to true.
+    ///
+    /// With `Some(buffer_size)`, makes the logger recover from failures of writing to its
+    /// log file, see [`recover_from_io_errors()`](#method.recover_from_io_errors).
+    pub fn o_recover_from_io_errors(mut self, buffer_size: Option<usize>) -> Logger {
+        self.flwb = self.flwb.o_recover_from_io_errors(buffer_size);
+        self
+    }
 }
 
-/// Used to control which messages are to be duplicated to stderr, when log_to_file() is used.
+/// Used to control which messages are to be duplicated to stderr or stdout,
+/// when log_to_file() is used.
+#[derive(Clone, Copy)]
 pub enum Duplicate {
     /// No messages are duplicated.
     None,
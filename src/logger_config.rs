@@ -0,0 +1,321 @@
+use formats;
+use log_specification::SpecFileFormat;
+use logger::Duplicate;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use writers::{Age, FileLogWriter, FileLogWriterBuilder, OverflowPolicy};
+use {FlexiLoggerError, FormatFunction};
+
+/// Describes a complete `Logger`, including its `FileLogWriter`s, such that it can be
+/// read from a configuration file.
+///
+/// `LoggerConfig` can be deserialized from a section of the program's own configuration
+/// file, or be read from a separate file with [`from_file()`](#method.from_file).
+/// [`Logger::from_config()`](struct.Logger.html#method.from_config) creates the `Logger`,
+/// which can then be started as usual.
+///
+/// All entries are optional. Durations are given as a number with one of the units
+/// `s`, `m`, `h`, or `d`, like `"30m"`; a plain number is interpreted as seconds.
+///
+/// A configuration in toml format might look like this:
+///
+/// ```toml
+/// spec = "info, mycrate::db = debug"
+/// log_to_file = true
+/// duplicate_to_stderr = "warn"
+/// format = "detailed"
+///
+/// [file]
+/// directory = "/var/log/myprog"
+/// rotate_over_size = 10_000_000
+/// max_backup = 5
+/// max_file_age = "30d"
+///
+/// [writers.alerts]
+/// spec = "warn"
+/// format_pattern = "{ts} {level} {msg}"
+///
+/// [writers.alerts.file]
+/// directory = "/var/log/myprog"
+/// ```
+///
+/// `LoggerConfig` is only available with the `specfile` feature.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggerConfig {
+    /// The log specification, like `"info, mycrate = debug"`;
+    /// if not given, the log specification is read from the environment variable `RUST_LOG`.
+    pub spec: Option<String>,
+    /// Makes the logger write to files, as described in `file`, rather than to stderr.
+    pub log_to_file: bool,
+    /// Makes the logger write to stdout, rather than to stderr.
+    pub log_to_stdout: bool,
+    /// The levels that are duplicated to stderr when logging to files:
+    /// `"none"`, `"error"`, `"warn"`, `"info"`, `"debug"`, `"trace"`, or `"all"`.
+    pub duplicate_to_stderr: Option<String>,
+    /// The levels that are duplicated to stdout when logging to files, see `duplicate_to_stderr`.
+    pub duplicate_to_stdout: Option<String>,
+    /// One of the provided format functions:
+    /// `"default"`, `"opt"`, `"detailed"`, `"with_thread"`, or `"json"`.
+    pub format: Option<String>,
+    /// A format pattern, see
+    /// [`Logger::format_pattern()`](struct.Logger.html#method.format_pattern);
+    /// takes precedence over `format`.
+    pub format_pattern: Option<String>,
+    /// Colors the log lines on stderr and stdout with the default palette.
+    pub colored: bool,
+    /// Colors the log lines on stderr and stdout with the given palette,
+    /// see [`Palette::parse()`](struct.Palette.html#method.parse).
+    pub palette: Option<String>,
+    /// Makes the logger log panics, see
+    /// [`Logger::log_panics()`](struct.Logger.html#method.log_panics).
+    pub log_panics: bool,
+    /// Makes the logger suppress consecutive identical log lines within the given duration,
+    /// like `"10s"`, see [`Logger::deduplicate()`](struct.Logger.html#method.deduplicate).
+    pub deduplicate: Option<String>,
+    /// The settings for the log files.
+    pub file: FileConfig,
+    /// Additional writers that write to their own files, by name.
+    pub writers: BTreeMap<String, WriterConfig>,
+}
+
+impl LoggerConfig {
+    /// Reads the configuration from a file.
+    ///
+    /// The format of the file is determined by its suffix:
+    /// `toml`, `json`, or `yaml` (or `yml`).
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<LoggerConfig, FlexiLoggerError> {
+        let format = SpecFileFormat::from_path(path.as_ref())?;
+        let s = fs::read_to_string(path)?;
+        format.deserialize(&s)
+    }
+
+    // The parsed entries, which the Logger applies.
+    pub(crate) fn duplicate_to_stderr_level(&self) -> Result<Option<Duplicate>, FlexiLoggerError> {
+        self.duplicate_to_stderr
+            .as_ref()
+            .map(|s| parse_duplicate(s))
+            .transpose()
+    }
+
+    pub(crate) fn duplicate_to_stdout_level(&self) -> Result<Option<Duplicate>, FlexiLoggerError> {
+        self.duplicate_to_stdout
+            .as_ref()
+            .map(|s| parse_duplicate(s))
+            .transpose()
+    }
+
+    pub(crate) fn deduplicate_window(&self) -> Result<Option<Duration>, FlexiLoggerError> {
+        self.deduplicate
+            .as_ref()
+            .map(|s| parse_duration(s))
+            .transpose()
+    }
+
+    pub(crate) fn format_function(&self) -> Result<Option<FormatFunction>, FlexiLoggerError> {
+        self.format.as_ref().map(|s| parse_format(s)).transpose()
+    }
+}
+
+/// The settings for the files of a `FileLogWriter`, as part of a
+/// [`LoggerConfig`](struct.LoggerConfig.html).
+///
+/// The entries correspond to the methods of
+/// [`FileLogWriterBuilder`](writers/struct.FileLogWriterBuilder.html).
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    /// The folder for the log files; by default, the folder where the program was started.
+    pub directory: Option<String>,
+    /// A string that is added to the log file names.
+    pub discriminant: Option<String>,
+    /// The suffix of the log files; the default is `"log"`, or the name of the writer
+    /// for additional writers.
+    pub suffix: Option<String>,
+    /// Leaves the timestamp out of the log file names.
+    pub suppress_timestamp: bool,
+    /// Prints an info message to stdout when a new file is used.
+    pub print_message: bool,
+    /// Appends to existing log files rather than truncating them.
+    pub append: bool,
+    /// The name of a symbolic link to the current log file (linux only).
+    pub create_symlink: Option<String>,
+    /// Rotates the log files when they exceed the given number of bytes.
+    pub rotate_over_size: Option<usize>,
+    /// Rotates the log files by age: `"hour"`, `"day"`, or a duration like `"6h"`.
+    pub rotate_over_age: Option<String>,
+    /// Limits the number of rotated log files.
+    pub max_backup: Option<usize>,
+    /// Deletes log files that are older than the given duration, like `"30d"`.
+    pub max_file_age: Option<String>,
+    /// Deletes the oldest log files when the log files together exceed the given number of bytes.
+    pub max_total_size: Option<usize>,
+    /// Compresses the rotated log files; requires the `compress` feature.
+    pub compress: bool,
+    /// Reopens the log file when it was renamed or deleted by someone else.
+    pub reopen_on_external_rotation: bool,
+    /// Keeps up to the given number of bytes of log lines in memory while writing to the file
+    /// fails, and writes them when writing succeeds again.
+    pub recovery_buffer_size: Option<usize>,
+    /// Writes in the background, with a queue of the given capacity.
+    pub async_capacity: Option<usize>,
+    /// What to do if the queue for writing in the background is full:
+    /// `"block"` (the default), `"drop_newest"`, or `"drop_oldest"`.
+    pub overflow_policy: Option<String>,
+}
+
+impl FileConfig {
+    // Applies the settings to the builder.
+    pub(crate) fn apply(
+        &self,
+        mut flwb: FileLogWriterBuilder,
+    ) -> Result<FileLogWriterBuilder, FlexiLoggerError> {
+        flwb = flwb
+            .o_directory(self.directory.clone())
+            .o_discriminant(self.discriminant.clone())
+            .o_print_message(self.print_message)
+            .o_append(self.append)
+            .o_create_symlink(self.create_symlink.clone())
+            .o_max_backup(self.max_backup)
+            .o_max_total_size(self.max_total_size)
+            .o_reopen_on_external_rotation(self.reopen_on_external_rotation)
+            .o_recover_from_io_errors(self.recovery_buffer_size);
+        if let Some(ref suffix) = self.suffix {
+            flwb = flwb.suffix(suffix.clone());
+        }
+        if self.suppress_timestamp {
+            flwb = flwb.suppress_timestamp();
+        }
+        if let Some(rotate_over_size) = self.rotate_over_size {
+            flwb = flwb.rotate_over_size(rotate_over_size);
+        }
+        if let Some(ref age) = self.rotate_over_age {
+            flwb = flwb.rotate_over_age(parse_age(age)?);
+        }
+        if let Some(ref max_file_age) = self.max_file_age {
+            flwb = flwb.max_file_age(parse_duration(max_file_age)?);
+        }
+        if self.compress {
+            flwb = compress(flwb)?;
+        }
+        if let Some(capacity) = self.async_capacity {
+            let overflow = match self.overflow_policy {
+                Some(ref s) => parse_overflow_policy(s)?,
+                None => OverflowPolicy::Block,
+            };
+            flwb = flwb.async_mode(capacity, overflow);
+        }
+        Ok(flwb)
+    }
+}
+
+/// The settings for an additional writer, as part of a
+/// [`LoggerConfig`](struct.LoggerConfig.html).
+///
+/// The writer is a [`FileLogWriter`](writers/struct.FileLogWriter.html), which is registered
+/// with the logger under its name in the `LoggerConfig`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WriterConfig {
+    /// A log specification for the writer, see
+    /// [`Logger::add_writer_with_spec()`](struct.Logger.html#method.add_writer_with_spec).
+    pub spec: Option<String>,
+    /// One of the provided format functions, see `LoggerConfig::format`.
+    pub format: Option<String>,
+    /// A format pattern; takes precedence over `format`.
+    pub format_pattern: Option<String>,
+    /// The settings for the files of the writer.
+    pub file: FileConfig,
+}
+
+impl WriterConfig {
+    // Creates the writer, with the name as suffix of its files, unless another one is configured.
+    pub(crate) fn file_log_writer(&self, name: &str) -> Result<FileLogWriter, FlexiLoggerError> {
+        let mut flwb = FileLogWriter::builder().suffix(name);
+        if let Some(ref format) = self.format {
+            flwb = flwb.format(parse_format(format)?);
+        }
+        flwb = flwb.o_format_pattern(self.format_pattern.clone());
+        self.file.apply(flwb)?.instantiate()
+    }
+}
+
+fn parse_error(what: &str, s: &str) -> FlexiLoggerError {
+    FlexiLoggerError::Parse(format!(
+        "invalid {} in logger configuration: \"{}\"",
+        what, s
+    ))
+}
+
+fn parse_duplicate(s: &str) -> Result<Duplicate, FlexiLoggerError> {
+    Ok(match s.to_lowercase().as_ref() {
+        "none" => Duplicate::None,
+        "error" => Duplicate::Error,
+        "warn" => Duplicate::Warn,
+        "info" => Duplicate::Info,
+        "debug" => Duplicate::Debug,
+        "trace" => Duplicate::Trace,
+        "all" => Duplicate::All,
+        _ => return Err(parse_error("duplicate level", s)),
+    })
+}
+
+fn parse_format(s: &str) -> Result<FormatFunction, FlexiLoggerError> {
+    Ok(match s {
+        "default" => formats::default_format,
+        "opt" => formats::opt_format,
+        "detailed" => formats::detailed_format,
+        "with_thread" => formats::with_thread,
+        "json" => formats::json_format,
+        _ => return Err(parse_error("format", s)),
+    })
+}
+
+fn parse_age(s: &str) -> Result<Age, FlexiLoggerError> {
+    Ok(match s {
+        "hour" => Age::Hour,
+        "day" => Age::Day,
+        _ => Age::Every(parse_duration(s).map_err(|_| parse_error("age", s))?),
+    })
+}
+
+fn parse_duration(s: &str) -> Result<Duration, FlexiLoggerError> {
+    let s = s.trim();
+    let (digits, factor) = match s.chars().last() {
+        Some('s') => (&s[..s.len() - 1], 1),
+        Some('m') => (&s[..s.len() - 1], 60),
+        Some('h') => (&s[..s.len() - 1], 60 * 60),
+        Some('d') => (&s[..s.len() - 1], 24 * 60 * 60),
+        _ => (s, 1),
+    };
+    digits
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(factor))
+        .map(Duration::from_secs)
+        .ok_or_else(|| parse_error("duration", s))
+}
+
+fn parse_overflow_policy(s: &str) -> Result<OverflowPolicy, FlexiLoggerError> {
+    Ok(match s {
+        "block" => OverflowPolicy::Block,
+        "drop_newest" => OverflowPolicy::DropNewest,
+        "drop_oldest" => OverflowPolicy::DropOldest,
+        _ => return Err(parse_error("overflow policy", s)),
+    })
+}
+
+#[cfg(feature = "compress")]
+fn compress(flwb: FileLogWriterBuilder) -> Result<FileLogWriterBuilder, FlexiLoggerError> {
+    Ok(flwb.compress())
+}
+
+#[cfg(not(feature = "compress"))]
+fn compress(_: FileLogWriterBuilder) -> Result<FileLogWriterBuilder, FlexiLoggerError> {
+    Err(FlexiLoggerError::Parse(
+        "compressing log files requires the feature compress".to_owned(),
+    ))
+}
@@ -0,0 +1,79 @@
+#![allow(unknown_lints)]
+#![allow(clippy::missing_const_for_thread_local)]
+
+use flexi_logger::FlexiLogger;
+use log::{Level, Log, Record};
+use std::backtrace::Backtrace;
+use std::cell::Cell;
+use std::panic;
+use std::sync::Arc;
+use std::thread;
+
+thread_local! {
+    static IN_PANIC_HOOK: Cell<bool> = Cell::new(false);
+}
+
+// Whether the current thread is logging a panic. Locks must then only be tried,
+// since the panicking code might hold them.
+pub fn in_panic_hook() -> bool {
+    IN_PANIC_HOOK.with(|in_panic_hook| in_panic_hook.get())
+}
+
+// Replaces the panic hook with one that logs panics as errors, with target `panic`,
+// in the same way as other records, but without checking the log specification,
+// so that it cannot suppress them, and flushes all writers.
+// If the primary writer fails to write the panic, the previous panic hook is called.
+pub fn install_panic_hook(flexi_logger: Arc<FlexiLogger>, with_backtrace: bool) {
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = if let Some(s) = payload.downcast_ref::<&str>() {
+            *s
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.as_str()
+        } else {
+            "Box<Any>"
+        };
+        let thread = thread::current();
+        let thread_name = thread.name().unwrap_or("<unnamed>");
+        let location = info.location();
+        let o_backtrace = if with_backtrace {
+            Some(Backtrace::force_capture())
+        } else {
+            None
+        };
+
+        let args = match (location, o_backtrace) {
+            (Some(location), Some(backtrace)) => format!(
+                "thread '{}' panicked at '{}', {}\nstack backtrace:\n{}",
+                thread_name, message, location, backtrace
+            ),
+            (Some(location), None) => format!(
+                "thread '{}' panicked at '{}', {}",
+                thread_name, message, location
+            ),
+            (None, Some(backtrace)) => format!(
+                "thread '{}' panicked at '{}'\nstack backtrace:\n{}",
+                thread_name, message, backtrace
+            ),
+            (None, None) => format!("thread '{}' panicked at '{}'", thread_name, message),
+        };
+        IN_PANIC_HOOK.with(|in_panic_hook| in_panic_hook.set(true));
+        let written = flexi_logger.fl_log(
+            &Record::builder()
+                .args(format_args!("{}", args))
+                .level(Level::Error)
+                .target("panic")
+                .module_path(Some("panic"))
+                .file(location.map(|l| l.file()))
+                .line(location.map(|l| l.line()))
+                .build(),
+            false,
+        );
+        flexi_logger.flush();
+        IN_PANIC_HOOK.with(|in_panic_hook| in_panic_hook.set(false));
+        if !written {
+            previous_hook(info);
+        }
+    }));
+}
@@ -1,61 +1,206 @@
+use atty::Stream;
+use colors::{supports_colors, write_colored, Palette};
+use error_handler::ErrorTarget;
+use formatter::Formatter;
 use log;
 use log::Record;
 use logger::Duplicate;
+use statistics::FileStatistics;
 use std::io;
 use std::io::Write;
+use std::mem;
+use std::sync::RwLock;
 
 use writers::FileLogWriter;
+use writers::FileLogWriterBuilder;
 use writers::LogWriter;
+use FlexiLoggerError;
 use FormatFunction;
 
-// Writes either to stderr or to a file.
-#[allow(unknown_lints)]
-#[allow(large_enum_variant)]
-pub enum PrimaryWriter {
-    StdErrWriter(StdErrWriter),
-    ExtendedFileWriter(ExtendedFileWriter),
+// The settings from which the primary writer is created; they are kept by the
+// ReconfigurationHandle for re-creating the primary writer at runtime.
+#[derive(Clone)]
+pub struct PrimaryWriterSettings {
+    pub log_to_stdout: bool,
+    pub duplicate_stderr: Duplicate,
+    pub duplicate_stdout: Duplicate,
+    pub o_palette: Option<Palette>,
+    pub format: FormatFunction,
+    pub o_format_pattern: Option<String>,
+    // already configured with the format function and the format pattern
+    pub flwb: FileLogWriterBuilder,
+}
+impl PrimaryWriterSettings {
+    // Creates a primary writer that writes to stdout or stderr.
+    pub fn stream_writer(&self) -> Result<PrimaryWriter, FlexiLoggerError> {
+        let formatter = match self.o_format_pattern {
+            Some(ref pattern) => Formatter::parse_pattern(pattern)?,
+            None => Formatter::Function(self.format),
+        };
+        Ok(if self.log_to_stdout {
+            PrimaryWriter::stdout(formatter, self.o_palette)
+        } else {
+            PrimaryWriter::stderr(formatter, self.o_palette)
+        })
+    }
+
+    // Creates a primary writer that writes to a file, as configured with the given builder.
+    pub fn file_writer(
+        &self,
+        flwb: FileLogWriterBuilder,
+    ) -> Result<PrimaryWriter, FlexiLoggerError> {
+        Ok(PrimaryWriter::file(
+            self.duplicate_stderr,
+            self.duplicate_stdout,
+            self.o_palette,
+            flwb.instantiate()?,
+        ))
+    }
+}
+
+// Writes either to stderr, or to stdout, or to a file.
+//
+// The output can be replaced while the logger is in use.
+// The ErrorTarget, once set by the logger, takes the errors of a file output that occur
+// in the background.
+pub struct PrimaryWriter {
+    output: RwLock<Output>,
+    o_error_target: RwLock<Option<ErrorTarget>>,
 }
 impl PrimaryWriter {
-    pub fn file(duplicate: Duplicate, w: FileLogWriter) -> PrimaryWriter {
-        PrimaryWriter::ExtendedFileWriter(ExtendedFileWriter { duplicate, w })
+    pub fn file(
+        duplicate_stderr: Duplicate,
+        duplicate_stdout: Duplicate,
+        o_palette: Option<Palette>,
+        w: FileLogWriter,
+    ) -> PrimaryWriter {
+        PrimaryWriter::new(Output::ExtendedFileWriter(ExtendedFileWriter {
+            duplicate_stderr,
+            duplicate_stdout,
+            o_stderr_palette: o_palette.filter(|_| supports_colors(Stream::Stderr)),
+            o_stdout_palette: o_palette.filter(|_| supports_colors(Stream::Stdout)),
+            w,
+        }))
+    }
+    pub fn stderr(formatter: Formatter, o_palette: Option<Palette>) -> PrimaryWriter {
+        PrimaryWriter::new(Output::StdErrWriter(StdErrWriter {
+            formatter,
+            o_palette: o_palette.filter(|_| supports_colors(Stream::Stderr)),
+        }))
+    }
+    pub fn stdout(formatter: Formatter, o_palette: Option<Palette>) -> PrimaryWriter {
+        PrimaryWriter::new(Output::StdOutWriter(StdOutWriter {
+            formatter,
+            o_palette: o_palette.filter(|_| supports_colors(Stream::Stdout)),
+        }))
     }
-    pub fn stderr(format: FormatFunction) -> PrimaryWriter {
-        PrimaryWriter::StdErrWriter(StdErrWriter { format })
+    fn new(output: Output) -> PrimaryWriter {
+        PrimaryWriter {
+            output: RwLock::new(output),
+            o_error_target: RwLock::new(None),
+        }
     }
 
     // Write out a log line.
     pub fn write(&self, record: &Record) -> io::Result<()> {
-        match *self {
-            PrimaryWriter::StdErrWriter(ref w) => w.write(record),
-            PrimaryWriter::ExtendedFileWriter(ref w) => w.write(record),
+        match *self.output.read().unwrap() {
+            Output::StdErrWriter(ref w) => w.write(record),
+            Output::StdOutWriter(ref w) => w.write(record),
+            Output::ExtendedFileWriter(ref w) => w.write(record),
+        }
+    }
+
+    // Write out already formatted log lines.
+    pub fn write_lines(&self, lines: &[u8]) -> io::Result<()> {
+        match *self.output.read().unwrap() {
+            Output::StdErrWriter(_) => io::stderr().write_all(lines),
+            Output::StdOutWriter(_) => io::stdout().write_all(lines),
+            Output::ExtendedFileWriter(ref w) => w.w.write_lines(lines),
         }
     }
 
     // Flush any buffered records.
     pub fn flush(&self) -> io::Result<()> {
-        match *self {
-            PrimaryWriter::StdErrWriter(ref w) => w.flush(),
-            PrimaryWriter::ExtendedFileWriter(ref w) => w.flush(),
+        self.output.read().unwrap().flush()
+    }
+
+    // Replaces the output with the output of the given primary writer;
+    // the previous output is flushed and closed.
+    pub fn replace(&self, other: PrimaryWriter) -> io::Result<()> {
+        let new_output = other.output.into_inner().unwrap();
+        if let Some(ref target) = *self.o_error_target.read().unwrap() {
+            new_output.connect_errors(target.clone());
+        }
+        let old_output = mem::replace(&mut *self.output.write().unwrap(), new_output);
+        // dropping the old output closes the file, if any
+        old_output.flush()
+    }
+
+    // Passes the errors of the output that occur in the background to the given target,
+    // also after the output is replaced.
+    pub fn connect_errors(&self, target: ErrorTarget) {
+        self.output.read().unwrap().connect_errors(target.clone());
+        *self.o_error_target.write().unwrap() = Some(target);
+    }
+
+    // Reopen the output file, if any.
+    pub fn reopen_output_file(&self) -> io::Result<()> {
+        match *self.output.read().unwrap() {
+            Output::StdErrWriter(_) | Output::StdOutWriter(_) => Ok(()),
+            Output::ExtendedFileWriter(ref w) => w.w.reopen_output_file(),
+        }
+    }
+
+    // The counters of the output file, if any.
+    pub fn file_statistics(&self) -> Option<FileStatistics> {
+        match *self.output.read().unwrap() {
+            Output::StdErrWriter(_) | Output::StdOutWriter(_) => None,
+            Output::ExtendedFileWriter(ref w) => Some(w.w.statistics()),
         }
     }
 
     pub fn validate_logs(&self, expected: &[(&'static str, &'static str, &'static str)]) -> bool {
+        match *self.output.read().unwrap() {
+            Output::StdErrWriter(_) | Output::StdOutWriter(_) => false,
+            Output::ExtendedFileWriter(ref w) => w.validate_logs(expected),
+        }
+    }
+}
+
+#[allow(unknown_lints)]
+#[allow(large_enum_variant)]
+#[allow(clippy::enum_variant_names)]
+enum Output {
+    StdErrWriter(StdErrWriter),
+    StdOutWriter(StdOutWriter),
+    ExtendedFileWriter(ExtendedFileWriter),
+}
+impl Output {
+    fn flush(&self) -> io::Result<()> {
         match *self {
-            PrimaryWriter::StdErrWriter(_) => false,
-            PrimaryWriter::ExtendedFileWriter(ref w) => w.validate_logs(expected),
+            Output::StdErrWriter(ref w) => w.flush(),
+            Output::StdOutWriter(ref w) => w.flush(),
+            Output::ExtendedFileWriter(ref w) => w.flush(),
+        }
+    }
+
+    fn connect_errors(&self, target: ErrorTarget) {
+        if let Output::ExtendedFileWriter(ref w) = *self {
+            w.w.error_sink().connect(target);
         }
     }
 }
 
 /// `StdErrWriter` writes logs to stderr.
 pub struct StdErrWriter {
-    format: FormatFunction,
+    formatter: Formatter,
+    o_palette: Option<Palette>,
 }
 
 impl StdErrWriter {
     #[inline]
     fn write(&self, record: &Record) -> io::Result<()> {
-        write_to_stderr(self.format, record)
+        write_to_stderr(&self.formatter, self.o_palette.as_ref(), record)
     }
 
     #[inline]
@@ -64,10 +209,33 @@ impl StdErrWriter {
     }
 }
 
-/// `ExtendedFileWriter` writes logs to stderr or to a `FileLogWriter`, and in the latter case
-/// can duplicate messages to stderr.
+/// `StdOutWriter` writes logs to stdout.
+pub struct StdOutWriter {
+    formatter: Formatter,
+    o_palette: Option<Palette>,
+}
+
+impl StdOutWriter {
+    #[inline]
+    fn write(&self, record: &Record) -> io::Result<()> {
+        write_to_stdout(&self.formatter, self.o_palette.as_ref(), record)
+    }
+
+    #[inline]
+    fn flush(&self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
+/// `ExtendedFileWriter` writes logs to a `FileLogWriter`, and can duplicate messages
+/// to stderr and to stdout.
+///
+/// Only the duplicates on stderr and stdout are colored, the file output is never colored.
 pub struct ExtendedFileWriter {
-    duplicate: Duplicate,
+    duplicate_stderr: Duplicate,
+    duplicate_stdout: Duplicate,
+    o_stderr_palette: Option<Palette>,
+    o_stdout_palette: Option<Palette>,
     w: FileLogWriter,
 }
 impl ExtendedFileWriter {
@@ -76,27 +244,65 @@ impl ExtendedFileWriter {
     }
 
     fn write(&self, record: &Record) -> io::Result<()> {
-        if match self.duplicate {
-            Duplicate::Error => record.level() == log::Level::Error,
-            Duplicate::Warn => record.level() <= log::Level::Warn,
-            Duplicate::Info => record.level() <= log::Level::Info,
-            Duplicate::Debug => record.level() <= log::Level::Debug,
-            Duplicate::Trace | Duplicate::All => true,
-            Duplicate::None => false,
-        } {
-            write_to_stderr(self.w.format(), record)?;
+        if is_duplicated(&self.duplicate_stderr, record.level()) {
+            write_to_stderr(self.w.formatter(), self.o_stderr_palette.as_ref(), record)?;
+        }
+        if is_duplicated(&self.duplicate_stdout, record.level()) {
+            write_to_stdout(self.w.formatter(), self.o_stdout_palette.as_ref(), record)?;
         }
         self.w.write(record)
     }
 
     fn flush(&self) -> io::Result<()> {
         self.w.flush()?;
-        io::stderr().flush()
+        io::stderr().flush()?;
+        io::stdout().flush()
+    }
+}
+
+fn is_duplicated(duplicate: &Duplicate, level: log::Level) -> bool {
+    match *duplicate {
+        Duplicate::Error => level == log::Level::Error,
+        Duplicate::Warn => level <= log::Level::Warn,
+        Duplicate::Info => level <= log::Level::Info,
+        Duplicate::Debug => level <= log::Level::Debug,
+        Duplicate::Trace | Duplicate::All => true,
+        Duplicate::None => false,
     }
 }
 
 #[inline]
-fn write_to_stderr(f: FormatFunction, record: &Record) -> io::Result<()> {
-    (f)(&mut io::stderr(), record)?;
-    io::stderr().write_all(b"\n")
+fn write_to_stderr(
+    formatter: &Formatter,
+    o_palette: Option<&Palette>,
+    record: &Record,
+) -> io::Result<()> {
+    let stderr = io::stderr();
+    let mut w = stderr.lock();
+    write_line(&mut w, formatter, o_palette, record)
+}
+
+#[inline]
+fn write_to_stdout(
+    formatter: &Formatter,
+    o_palette: Option<&Palette>,
+    record: &Record,
+) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut w = stdout.lock();
+    write_line(&mut w, formatter, o_palette, record)
+}
+
+#[inline]
+fn write_line<W: io::Write>(
+    w: &mut W,
+    formatter: &Formatter,
+    o_palette: Option<&Palette>,
+    record: &Record,
+) -> io::Result<()> {
+    match o_palette {
+        None => formatter.format(w, record)?,
+        Some(palette) => write_colored(palette, formatter, w, record)?,
+    }
+    w.write_all(b"\n")
 }
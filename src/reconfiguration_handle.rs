@@ -1,8 +1,16 @@
 use log_specification::LogSpecification;
-use primary_writer::PrimaryWriter;
+use primary_writer::{PrimaryWriter, PrimaryWriterSettings};
+use statistics::{Statistics, StatisticsHandle};
 use std::borrow::Borrow;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
 use std::sync::Arc;
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use writers::FileLogWriterBuilder;
+use FlexiLoggerError;
 
 /// Allows reconfiguring the logger while it is in use
 /// (see [`Logger::start_reconfigurable()`](struct.Logger.html#method.start_reconfigurable) ).
@@ -41,21 +49,203 @@ use std::sync::RwLock;
 ///     // ...
 /// }
 /// ```
+///
+/// # Temporary log specifications
+///
+/// With [`push_temp_spec()`](#method.push_temp_spec), a log specification can be activated
+/// temporarily, e.g. for intensifying the logging of a critical section;
+/// [`pop_temp_spec()`](#method.pop_temp_spec) reactivates the log specification that was
+/// active before.
+/// Temporary log specifications are kept on a stack, so several components can push and pop
+/// their own specifications without having to remember the previous ones.
+///
+/// ```rust
+/// use flexi_logger::Logger;
+/// use std::time::Duration;
+///
+/// let mut logger_handle = Logger::with_str("info")
+///         .start_reconfigurable()
+///         .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+///
+/// logger_handle.parse_and_push_temp_spec("info, critical_mod = trace");
+/// // ... critical stuff happens here ...
+/// logger_handle.pop_temp_spec();
+///
+/// // intensify logging for the next 10 seconds
+/// logger_handle.push_temp_spec_for(
+///     flexi_logger::LogSpecification::parse("debug"),
+///     Duration::from_secs(10),
+/// );
+/// ```
 pub struct ReconfigurationHandle {
     spec: Arc<RwLock<LogSpecification>>,
+    spec_stack: Arc<Mutex<SpecStack>>,
+    o_timer: Option<Sender<(Instant, u64)>>,
     primary_writer: Arc<PrimaryWriter>,
+    primary_writer_settings: PrimaryWriterSettings,
+    other_writer_specs: HashMap<String, Arc<RwLock<LogSpecification>>>,
+    statistics_handle: StatisticsHandle,
 }
 impl ReconfigurationHandle {
     /// Allows specifying a new LogSpecification for the current logger.
+    ///
+    /// If temporary log specifications are active, they stay active, and the new
+    /// LogSpecification becomes active when all of them are removed.
     pub fn set_new_spec(&mut self, new_spec: LogSpecification) {
-        let mut guard = self.spec.write().unwrap(/* not sure if we should expose this */);
-        guard.reconfigure(new_spec);
+        let mut spec_stack = self.spec_stack.lock().unwrap();
+        spec_stack.layers[0].1 = new_spec;
+        spec_stack.activate_top(&self.spec);
     }
 
     /// Allows specifying a new LogSpecification for the current logger.
     pub fn parse_new_spec(&mut self, spec: &str) {
-        let mut guard = self.spec.write().unwrap(/* not sure if we should expose this */);
-        guard.reconfigure(LogSpecification::parse(spec));
+        self.set_new_spec(LogSpecification::parse(spec));
+    }
+
+    /// Returns a copy of the currently active LogSpecification.
+    pub fn current_spec(&self) -> LogSpecification {
+        self.spec.read().unwrap().clone()
+    }
+
+    /// Activates the given LogSpecification temporarily, until
+    /// [`pop_temp_spec()`](#method.pop_temp_spec) is called.
+    pub fn push_temp_spec(&mut self, new_spec: LogSpecification) {
+        self.spec_stack.lock().unwrap().push(new_spec, &self.spec);
+    }
+
+    /// Activates the given LogSpecification temporarily, see
+    /// [`push_temp_spec()`](#method.push_temp_spec).
+    pub fn parse_and_push_temp_spec(&mut self, spec: &str) {
+        self.push_temp_spec(LogSpecification::parse(spec));
+    }
+
+    /// Removes the most recently pushed temporary LogSpecification, and reactivates
+    /// the LogSpecification that was active before it was pushed.
+    ///
+    /// Returns the removed LogSpecification, or None if no temporary LogSpecification is active.
+    pub fn pop_temp_spec(&mut self) -> Option<LogSpecification> {
+        let mut spec_stack = self.spec_stack.lock().unwrap();
+        if spec_stack.layers.len() > 1 {
+            let (_, popped_spec) = spec_stack.layers.pop().unwrap();
+            spec_stack.activate_top(&self.spec);
+            Some(popped_spec)
+        } else {
+            None
+        }
+    }
+
+    /// Activates the given LogSpecification temporarily, like
+    /// [`push_temp_spec()`](#method.push_temp_spec), and removes it automatically
+    /// after the given duration, unless it was popped before.
+    ///
+    /// If other temporary LogSpecifications were pushed in the meantime, they stay active,
+    /// and popping them later skips the expired LogSpecification.
+    ///
+    /// The expired LogSpecifications are removed by a single thread, which is started
+    /// with the first call.
+    pub fn push_temp_spec_for(&mut self, new_spec: LogSpecification, duration: Duration) {
+        let id = self.spec_stack.lock().unwrap().push(new_spec, &self.spec);
+        if self.o_timer.is_none() {
+            self.o_timer = start_timer(Arc::clone(&self.spec), Arc::clone(&self.spec_stack));
+        }
+        if let Some(ref timer) = self.o_timer {
+            timer.send((Instant::now() + duration, id)).ok();
+        }
+    }
+
+    /// Allows specifying a new LogSpecification for the additional writer with the given name.
+    ///
+    /// This only works for writers that were registered with a LogSpecification
+    /// (see [`Logger::add_writer_with_spec()`](struct.Logger.html#method.add_writer_with_spec)).
+    pub fn set_new_writer_spec(&mut self, writer_name: &str, new_spec: LogSpecification) {
+        match self.other_writer_specs.get(writer_name) {
+            None => eprintln!(
+                "FlexiLogger: no log specification for writer {}",
+                writer_name
+            ),
+            Some(spec) => {
+                let mut guard = spec.write().unwrap(/* not sure if we should expose this */);
+                guard.reconfigure(new_spec);
+            }
+        }
+    }
+
+    /// Allows specifying a new LogSpecification for the additional writer with the given name.
+    ///
+    /// See [`set_new_writer_spec()`](#method.set_new_writer_spec).
+    pub fn parse_new_writer_spec(&mut self, writer_name: &str, spec: &str) {
+        self.set_new_writer_spec(writer_name, LogSpecification::parse(spec));
+    }
+
+    /// Redirects the primary log output to a file, which is configured by modifying
+    /// a `FileLogWriterBuilder`.
+    ///
+    /// The builder that is given to the closure carries the file settings that were used
+    /// when the logger was started (even if it did not log to a file)
+    /// or when this method was called the last time, e.g. the directory, the discriminant,
+    /// the rotation, and the format. This allows changing only what needs to be changed:
+    ///
+    /// ```rust
+    /// use flexi_logger::Logger;
+    ///
+    /// let mut logger_handle = Logger::with_str("info")
+    ///         .rotate_over_size(1_000_000)
+    ///         .start_reconfigurable()
+    ///         .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+    ///
+    /// // ... read the configuration, which tells us the data directory ...
+    /// # let data_dir = "log_files/data_dir";
+    ///
+    /// logger_handle
+    ///     .reconfigure_file_output(|flwb| flwb.directory(data_dir).discriminant("daemon"))
+    ///     .unwrap_or_else(|e| panic!("Redirecting the log output failed with {}", e));
+    /// ```
+    ///
+    /// The new file is opened before the previous output is given up, so if opening it fails,
+    /// the previous output stays active. Otherwise, the previous output is flushed,
+    /// and its file, if any, is closed.
+    /// Duplication to stderr and stdout, and coloring, are kept as configured on the `Logger`.
+    pub fn reconfigure_file_output<F>(&mut self, f: F) -> Result<(), FlexiLoggerError>
+    where
+        F: FnOnce(FileLogWriterBuilder) -> FileLogWriterBuilder,
+    {
+        let flwb = f(self.primary_writer_settings.flwb.clone());
+        let new_primary_writer = self.primary_writer_settings.file_writer(flwb.clone())?;
+        self.primary_writer_settings.flwb = flwb;
+        self.primary_writer.replace(new_primary_writer)?;
+        Ok(())
+    }
+
+    /// Redirects the primary log output from the file back to stderr,
+    /// or to stdout, if the logger was configured with
+    /// [`Logger::log_to_stdout()`](struct.Logger.html#method.log_to_stdout).
+    ///
+    /// The previous output is flushed, and its file, if any, is closed.
+    pub fn disable_file_output(&mut self) -> Result<(), FlexiLoggerError> {
+        let new_primary_writer = self.primary_writer_settings.stream_writer()?;
+        self.primary_writer.replace(new_primary_writer)?;
+        Ok(())
+    }
+
+    /// Closes the current log file and opens it again under the same path,
+    /// e.g. after it was renamed or deleted by `logrotate`.
+    ///
+    /// This method can be called e.g. from a thread that handles `SIGHUP`;
+    /// it has no effect if the logger does not write to a file.
+    pub fn reopen_output_file(&self) -> Result<(), FlexiLoggerError> {
+        self.primary_writer.reopen_output_file()?;
+        Ok(())
+    }
+
+    /// Returns a snapshot of the counters of the logger.
+    pub fn statistics(&self) -> Statistics {
+        self.statistics_handle.statistics()
+    }
+
+    /// Returns a handle for getting snapshots of the counters of the logger,
+    /// which can be passed e.g. to a monitoring thread.
+    pub fn statistics_handle(&self) -> StatisticsHandle {
+        self.statistics_handle.clone()
     }
 
     #[doc(hidden)]
@@ -65,12 +255,117 @@ impl ReconfigurationHandle {
     }
 }
 
+// The initial log specification and the temporary log specifications that were pushed on top
+// of it, each with an id; the topmost one is the active one.
+struct SpecStack {
+    layers: Vec<(u64, LogSpecification)>,
+    next_id: u64,
+}
+impl SpecStack {
+    fn push(&mut self, new_spec: LogSpecification, spec: &RwLock<LogSpecification>) -> u64 {
+        self.next_id += 1;
+        self.layers.push((self.next_id, new_spec));
+        self.activate_top(spec);
+        self.next_id
+    }
+
+    // Removes the layer with the given id, if it is still there.
+    fn remove(&mut self, id: u64, spec: &RwLock<LogSpecification>) {
+        if let Some(idx) = self.layers.iter().position(|&(layer_id, _)| layer_id == id) {
+            self.layers.remove(idx);
+            if idx == self.layers.len() {
+                self.activate_top(spec);
+            }
+        }
+    }
+
+    fn activate_top(&self, spec: &RwLock<LogSpecification>) {
+        let top_spec = &self.layers.last().unwrap(/* never empty */).1;
+        let mut guard = spec.write().unwrap(/* not sure if we should expose this */);
+        guard.reconfigure(top_spec.clone());
+    }
+}
+
+// Starts the thread that removes the temporary log specifications when they expire.
+fn start_timer(
+    spec: Arc<RwLock<LogSpecification>>,
+    spec_stack: Arc<Mutex<SpecStack>>,
+) -> Option<Sender<(Instant, u64)>> {
+    let (sender, receiver) = channel();
+    match thread::Builder::new()
+        .name("flexi_logger-temp-spec".to_string())
+        .spawn(move || run_timer(&receiver, &spec, &spec_stack))
+    {
+        Ok(_) => Some(sender),
+        Err(e) => {
+            eprintln!(
+                "FlexiLogger: starting the thread for the temporary log specifications \
+                 failed with {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+// Receives the expiration times of temporary log specifications and removes them when they
+// are due; when the ReconfigurationHandle is dropped, the pending ones are still removed.
+fn run_timer(
+    receiver: &Receiver<(Instant, u64)>,
+    spec: &RwLock<LogSpecification>,
+    spec_stack: &Mutex<SpecStack>,
+) {
+    let mut expirations = BinaryHeap::new();
+    loop {
+        let received = match expirations.peek() {
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            Some(&Reverse((expiration, _))) => {
+                let now = Instant::now();
+                if expiration > now {
+                    receiver.recv_timeout(expiration - now)
+                } else {
+                    Err(RecvTimeoutError::Timeout)
+                }
+            }
+        };
+        match received {
+            Ok(expiration) => expirations.push(Reverse(expiration)),
+            Err(RecvTimeoutError::Timeout) => {
+                let Reverse((_, id)) = expirations.pop().unwrap();
+                spec_stack.lock().unwrap().remove(id, spec);
+            }
+            Err(RecvTimeoutError::Disconnected) => match expirations.pop() {
+                None => return,
+                Some(Reverse((expiration, id))) => {
+                    let now = Instant::now();
+                    if expiration > now {
+                        thread::sleep(expiration - now);
+                    }
+                    spec_stack.lock().unwrap().remove(id, spec);
+                }
+            },
+        }
+    }
+}
+
 pub fn reconfiguration_handle(
     spec: Arc<RwLock<LogSpecification>>,
     primary_writer: Arc<PrimaryWriter>,
+    primary_writer_settings: PrimaryWriterSettings,
+    other_writer_specs: HashMap<String, Arc<RwLock<LogSpecification>>>,
+    statistics_handle: StatisticsHandle,
 ) -> ReconfigurationHandle {
+    let initial_spec = spec.read().unwrap().clone();
     ReconfigurationHandle {
         spec,
+        spec_stack: Arc::new(Mutex::new(SpecStack {
+            layers: vec![(0, initial_spec)],
+            next_id: 0,
+        })),
+        o_timer: None,
         primary_writer,
+        primary_writer_settings,
+        other_writer_specs,
+        statistics_handle,
     }
 }
@@ -0,0 +1,248 @@
+use log::Level;
+use primary_writer::PrimaryWriter;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use writers::LogWriter;
+
+lazy_static! {
+    // The handle of the logger that was started last, for StatisticsHandle::current().
+    static ref CURRENT: Mutex<Option<StatisticsHandle>> = Mutex::new(None);
+}
+
+/// A snapshot of the counters that `flexi_logger` maintains while it is in use,
+/// see [`StatisticsHandle`](struct.StatisticsHandle.html).
+///
+/// Only log calls that reach the logger are counted; log calls on a level that is deeper
+/// than the deepest level of the log specification are usually discarded by the `log` crate
+/// before they reach the logger.
+#[derive(Clone, Debug, Default)]
+pub struct Statistics {
+    /// The number of log records per level that were written to the primary output;
+    /// failed writes are only counted in `write_errors`.
+    pub records: LevelCounts,
+    /// The number of log records that were suppressed by the module filters of the
+    /// log specification.
+    pub suppressed_by_spec: u64,
+    /// The number of log records that were suppressed by the text filters of the
+    /// log specification.
+    pub suppressed_by_text_filter: u64,
+    /// The number of log records that were dropped due to the limits of the log specification.
+    pub suppressed_by_limit: u64,
+    /// The number of log records that were suppressed as repetitions,
+    /// see [`Logger::deduplicate()`](struct.Logger.html#method.deduplicate).
+    pub suppressed_as_duplicate: u64,
+    /// The number of failed writes to the primary output.
+    pub write_errors: u64,
+    /// The number of failed flushes of the primary output.
+    pub flush_errors: u64,
+    /// The number of writer names in log targets that denote no writer.
+    pub bad_writer_names: u64,
+    /// The counters of the current log file, if the primary output is a file.
+    pub file: Option<FileStatistics>,
+    /// The counters of the additional writers, by name.
+    pub writers: BTreeMap<String, WriterStatistics>,
+}
+
+/// Numbers of log records per level.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct LevelCounts {
+    /// The number of error records.
+    pub error: u64,
+    /// The number of warn records.
+    pub warn: u64,
+    /// The number of info records.
+    pub info: u64,
+    /// The number of debug records.
+    pub debug: u64,
+    /// The number of trace records.
+    pub trace: u64,
+}
+
+impl LevelCounts {
+    /// Returns the number of records of the given level.
+    pub fn get(&self, level: Level) -> u64 {
+        match level {
+            Level::Error => self.error,
+            Level::Warn => self.warn,
+            Level::Info => self.info,
+            Level::Debug => self.debug,
+            Level::Trace => self.trace,
+        }
+    }
+
+    /// Returns the number of records of all levels.
+    pub fn total(&self) -> u64 {
+        self.error + self.warn + self.info + self.debug + self.trace
+    }
+}
+
+/// The counters of an additional writer, as part of [`Statistics`](struct.Statistics.html).
+#[derive(Clone, Debug, Default)]
+pub struct WriterStatistics {
+    /// The number of log records that were written by the writer;
+    /// failed writes are only counted in `write_errors`.
+    pub records: u64,
+    /// The number of log records that were suppressed by the log specification of the writer.
+    pub suppressed_by_spec: u64,
+    /// The number of log records that were dropped due to the limits of the
+    /// log specification of the writer.
+    pub suppressed_by_limit: u64,
+    /// The number of failed writes.
+    pub write_errors: u64,
+    /// The number of failed flushes.
+    pub flush_errors: u64,
+    /// The counters of the log file, if the writer is a `FileLogWriter`.
+    pub file: Option<FileStatistics>,
+}
+
+/// The counters of a [`FileLogWriter`](writers/struct.FileLogWriter.html).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FileStatistics {
+    /// The number of bytes that were written to the log files.
+    pub bytes_written: u64,
+    /// The number of rotations to a new log file.
+    pub rotations: u64,
+}
+
+/// Provides snapshots of the counters of the logger.
+///
+/// The handle of a logger started with
+/// [`Logger::start_reconfigurable()`](struct.Logger.html#method.start_reconfigurable)
+/// is available from its
+/// [`ReconfigurationHandle`](struct.ReconfigurationHandle.html#method.statistics_handle);
+/// the handle of the logger that was started last, with any of the start methods,
+/// is available from [`StatisticsHandle::current()`](#method.current).
+///
+/// ```rust
+/// use flexi_logger::{Logger, StatisticsHandle};
+///
+/// Logger::with_str("info")
+///     .start()
+///     .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+///
+/// // ...
+///
+/// let statistics = StatisticsHandle::current().unwrap().statistics();
+/// println!("{} log lines were written", statistics.records.total());
+/// ```
+#[derive(Clone)]
+pub struct StatisticsHandle {
+    counters: Arc<LoggerCounters>,
+    primary_writer: Arc<PrimaryWriter>,
+    other_writers: Arc<HashMap<String, Box<dyn LogWriter>>>,
+}
+
+impl StatisticsHandle {
+    pub(crate) fn new(
+        counters: Arc<LoggerCounters>,
+        primary_writer: Arc<PrimaryWriter>,
+        other_writers: Arc<HashMap<String, Box<dyn LogWriter>>>,
+    ) -> StatisticsHandle {
+        StatisticsHandle {
+            counters,
+            primary_writer,
+            other_writers,
+        }
+    }
+
+    /// Returns the handle of the logger that was started last, if any.
+    pub fn current() -> Option<StatisticsHandle> {
+        CURRENT.lock().unwrap().clone()
+    }
+
+    // Makes this the handle that current() returns.
+    pub(crate) fn make_current(&self) {
+        *CURRENT.lock().unwrap() = Some(self.clone());
+    }
+
+    /// Returns a snapshot of the counters.
+    pub fn statistics(&self) -> Statistics {
+        let counters = &self.counters;
+        Statistics {
+            records: LevelCounts {
+                error: get(&counters.records[0]),
+                warn: get(&counters.records[1]),
+                info: get(&counters.records[2]),
+                debug: get(&counters.records[3]),
+                trace: get(&counters.records[4]),
+            },
+            suppressed_by_spec: get(&counters.suppressed_by_spec),
+            suppressed_by_text_filter: get(&counters.suppressed_by_text_filter),
+            suppressed_by_limit: get(&counters.suppressed_by_limit),
+            suppressed_as_duplicate: get(&counters.suppressed_as_duplicate),
+            write_errors: get(&counters.write_errors),
+            flush_errors: get(&counters.flush_errors),
+            bad_writer_names: get(&counters.bad_writer_names),
+            file: self.primary_writer.file_statistics(),
+            writers: counters
+                .writers
+                .iter()
+                .map(|(name, writer_counters)| {
+                    (
+                        name.clone(),
+                        WriterStatistics {
+                            records: get(&writer_counters.records),
+                            suppressed_by_spec: get(&writer_counters.suppressed_by_spec),
+                            suppressed_by_limit: get(&writer_counters.suppressed_by_limit),
+                            write_errors: get(&writer_counters.write_errors),
+                            flush_errors: get(&writer_counters.flush_errors),
+                            file: self
+                                .other_writers
+                                .get(name)
+                                .and_then(|writer| writer.file_statistics()),
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+// The counters of the logger.
+#[derive(Default)]
+pub struct LoggerCounters {
+    records: [AtomicU64; 5],
+    pub suppressed_by_spec: AtomicU64,
+    pub suppressed_by_text_filter: AtomicU64,
+    pub suppressed_by_limit: AtomicU64,
+    pub suppressed_as_duplicate: AtomicU64,
+    pub write_errors: AtomicU64,
+    pub flush_errors: AtomicU64,
+    pub bad_writer_names: AtomicU64,
+    pub writers: HashMap<String, WriterCounters>,
+}
+
+impl LoggerCounters {
+    pub fn new<'a, I: Iterator<Item = &'a String>>(writer_names: I) -> LoggerCounters {
+        LoggerCounters {
+            writers: writer_names
+                .map(|name| (name.clone(), WriterCounters::default()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn records(&self, level: Level) -> &AtomicU64 {
+        &self.records[level as usize - 1]
+    }
+}
+
+// The counters of an additional writer.
+#[derive(Default)]
+pub struct WriterCounters {
+    pub records: AtomicU64,
+    pub suppressed_by_spec: AtomicU64,
+    pub suppressed_by_limit: AtomicU64,
+    pub write_errors: AtomicU64,
+    pub flush_errors: AtomicU64,
+}
+
+#[inline]
+pub fn increment(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+fn get(counter: &AtomicU64) -> u64 {
+    counter.load(Ordering::Relaxed)
+}
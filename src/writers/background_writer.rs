@@ -0,0 +1,321 @@
+use error_handler::ErrorSink;
+use std::cmp::max;
+use std::collections::VecDeque;
+use std::io;
+use std::mem;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// Defines what happens to a log line that is written by a `FileLogWriter` in async mode
+/// while the queue to the background thread is full.
+///
+/// See [`FileLogWriterBuilder::async_mode()`](struct.FileLogWriterBuilder.html#method.async_mode).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OverflowPolicy {
+    /// The log call waits until the queue has space again; no log line is lost.
+    Block,
+    /// The new log line is dropped.
+    DropNewest,
+    /// The oldest log line in the queue is dropped to make space for the new one.
+    DropOldest,
+}
+
+// The target of a BackgroundWriter; is owned and used by the background thread.
+pub trait LineSink: Send + 'static {
+    fn write_line(&mut self, line: &[u8]) -> io::Result<()>;
+    fn flush(&mut self) -> io::Result<()>;
+}
+
+enum Message {
+    Line(Vec<u8>),
+    Flush(u64),
+    Shutdown,
+}
+
+struct QueueState {
+    messages: VecDeque<Message>,
+    no_of_lines: usize,
+    no_of_dropped: u64,
+    flush_requested: u64,
+    flush_done: u64,
+    // false when the background thread has ended, e.g. because it panicked
+    thread_alive: bool,
+}
+
+struct Queue {
+    capacity: usize,
+    overflow: OverflowPolicy,
+    state: Mutex<QueueState>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    flushed: Condvar,
+    error_sink: ErrorSink,
+}
+
+// Hands formatted log lines through a bounded queue to a dedicated thread,
+// which writes them to its LineSink.
+//
+// Only lines count against the capacity of the queue, flush and shutdown requests never
+// get lost. Dropping the BackgroundWriter drains the queue and joins the thread.
+pub struct BackgroundWriter {
+    queue: Arc<Queue>,
+    o_join_handle: Option<JoinHandle<()>>,
+}
+impl BackgroundWriter {
+    pub fn new<S: LineSink>(
+        capacity: usize,
+        overflow: OverflowPolicy,
+        mut sink: S,
+        error_sink: ErrorSink,
+    ) -> io::Result<BackgroundWriter> {
+        let queue = Arc::new(Queue {
+            capacity: max(capacity, 1),
+            overflow,
+            state: Mutex::new(QueueState {
+                messages: VecDeque::new(),
+                no_of_lines: 0,
+                no_of_dropped: 0,
+                flush_requested: 0,
+                flush_done: 0,
+                thread_alive: true,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            flushed: Condvar::new(),
+            error_sink,
+        });
+
+        let t_queue = Arc::clone(&queue);
+        let join_handle = thread::Builder::new()
+            .name("flexi_logger-writer".to_string())
+            .spawn(move || t_queue.work(&mut sink))?;
+
+        Ok(BackgroundWriter {
+            queue,
+            o_join_handle: Some(join_handle),
+        })
+    }
+
+    // Enqueues a complete log line, applying the overflow policy if the queue is full;
+    // gives the line back if the background thread has ended.
+    pub fn write(&self, line: Vec<u8>) -> Result<(), Vec<u8>> {
+        let queue = &self.queue;
+        let mut state = queue.state.lock().unwrap();
+        if !state.thread_alive {
+            return Err(line);
+        }
+        if state.no_of_lines >= queue.capacity {
+            match queue.overflow {
+                OverflowPolicy::Block => {
+                    while state.no_of_lines >= queue.capacity && state.thread_alive {
+                        state = queue.not_full.wait(state).unwrap();
+                    }
+                    if !state.thread_alive {
+                        return Err(line);
+                    }
+                }
+                OverflowPolicy::DropNewest => {
+                    state.no_of_dropped += 1;
+                    return Ok(());
+                }
+                OverflowPolicy::DropOldest => {
+                    let o_idx = state
+                        .messages
+                        .iter()
+                        .position(|m| matches!(*m, Message::Line(_)));
+                    if let Some(idx) = o_idx {
+                        state.messages.remove(idx);
+                        state.no_of_lines -= 1;
+                        state.no_of_dropped += 1;
+                    }
+                }
+            }
+        }
+        state.messages.push_back(Message::Line(line));
+        state.no_of_lines += 1;
+        queue.not_empty.notify_one();
+        Ok(())
+    }
+
+    // Waits until all lines that were enqueued before are written and flushed;
+    // returns false if the background thread has ended.
+    pub fn flush(&self) -> bool {
+        let queue = &self.queue;
+        let mut state = queue.state.lock().unwrap();
+        state.flush_requested += 1;
+        let ticket = state.flush_requested;
+        state.messages.push_back(Message::Flush(ticket));
+        queue.not_empty.notify_one();
+        while state.flush_done < ticket && state.thread_alive {
+            state = queue.flushed.wait(state).unwrap();
+        }
+        state.flush_done >= ticket
+    }
+}
+
+impl Drop for BackgroundWriter {
+    fn drop(&mut self) {
+        {
+            let mut state = self.queue.state.lock().unwrap();
+            state.messages.push_back(Message::Shutdown);
+            self.queue.not_empty.notify_one();
+        }
+        if let Some(join_handle) = self.o_join_handle.take() {
+            join_handle.join().unwrap_or_else(|_| {
+                eprintln!("FlexiLogger: the background writer thread panicked");
+            });
+        }
+    }
+}
+
+impl Queue {
+    // The loop of the background thread.
+    fn work<S: LineSink>(&self, sink: &mut S) {
+        let _alive = AliveGuard(self);
+        loop {
+            let (message, no_of_dropped) = {
+                let mut state = self.state.lock().unwrap();
+                while state.messages.is_empty() {
+                    state = self.not_empty.wait(state).unwrap();
+                }
+                let message = state.messages.pop_front().unwrap(/*cannot fail*/);
+                if let Message::Line(_) = message {
+                    state.no_of_lines -= 1;
+                    self.not_full.notify_one();
+                }
+                (message, mem::replace(&mut state.no_of_dropped, 0))
+            };
+
+            if no_of_dropped > 0 {
+                let note = format!(
+                    "FlexiLogger: {} log line(s) dropped because the queue was full\n",
+                    no_of_dropped
+                );
+                sink.write_line(note.as_bytes())
+                    .unwrap_or_else(|e| self.error_sink.write_failed(e));
+            }
+
+            match message {
+                Message::Line(line) => {
+                    sink.write_line(&line)
+                        .unwrap_or_else(|e| self.error_sink.write_failed(e));
+                }
+                Message::Flush(ticket) => {
+                    sink.flush()
+                        .unwrap_or_else(|e| self.error_sink.flush_failed(e));
+                    let mut state = self.state.lock().unwrap();
+                    state.flush_done = ticket;
+                    self.flushed.notify_all();
+                }
+                Message::Shutdown => {
+                    sink.flush()
+                        .unwrap_or_else(|e| self.error_sink.flush_failed(e));
+                    return;
+                }
+            }
+        }
+    }
+}
+
+// Marks the background thread as ended when it returns or panics, and wakes up
+// the threads that wait for it.
+struct AliveGuard<'a>(&'a Queue);
+impl<'a> Drop for AliveGuard<'a> {
+    fn drop(&mut self) {
+        let queue = self.0;
+        let mut state = match queue.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        state.thread_alive = false;
+        queue.not_full.notify_all();
+        queue.flushed.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BackgroundWriter, LineSink, OverflowPolicy};
+    use error_handler::{ErrorHandler, ErrorPolicy, ErrorSink, ErrorTarget};
+    use statistics::LoggerCounters;
+    use std::io;
+    use std::sync::atomic::Ordering;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    struct FailingSink;
+    impl LineSink for FailingSink {
+        fn write_line(&mut self, _line: &[u8]) -> io::Result<()> {
+            Err(io::Error::new(io::ErrorKind::Other, "disk is broken"))
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Err(io::Error::new(io::ErrorKind::Other, "disk is gone"))
+        }
+    }
+
+    struct PanickingSink;
+    impl LineSink for PanickingSink {
+        fn write_line(&mut self, _line: &[u8]) -> io::Result<()> {
+            panic!("this sink is broken");
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_dead_thread() {
+        let writer = BackgroundWriter::new(
+            1,
+            OverflowPolicy::Block,
+            PanickingSink,
+            ErrorSink::default(),
+        )
+        .unwrap();
+        assert_eq!(writer.write(b"first\n".to_vec()), Ok(()));
+        // the thread panics on the first line; neither blocking writes nor flushes hang
+        while writer.write(b"more\n".to_vec()).is_ok() {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(writer.write(b"last\n".to_vec()), Err(b"last\n".to_vec()));
+        assert!(!writer.flush());
+    }
+
+    #[test]
+    fn test_errors_go_to_the_error_target() {
+        let errors = Arc::new(Mutex::new(Vec::<String>::new()));
+        let errors_clone = Arc::clone(&errors);
+        let handler = ErrorHandler::new(
+            ErrorPolicy::Callback(Box::new(move |e| {
+                errors_clone.lock().unwrap().push(e.to_string());
+            })),
+            None,
+        );
+        let counters = Arc::new(LoggerCounters::new(["async".to_string()].iter()));
+        let error_sink = ErrorSink::default();
+        error_sink.connect(ErrorTarget::new(
+            Arc::new(handler),
+            Arc::clone(&counters),
+            "async",
+        ));
+
+        let writer =
+            BackgroundWriter::new(10, OverflowPolicy::Block, FailingSink, error_sink).unwrap();
+        assert_eq!(writer.write(b"first\n".to_vec()), Ok(()));
+        assert_eq!(writer.write(b"second\n".to_vec()), Ok(()));
+        assert!(writer.flush());
+
+        assert_eq!(
+            *errors.lock().unwrap(),
+            vec![
+                "writing log line to async failed with disk is broken".to_string(),
+                "writing log line to async failed with disk is broken".to_string(),
+                "flushing async failed with disk is gone".to_string(),
+            ]
+        );
+        let writer_counters = &counters.writers["async"];
+        assert_eq!(writer_counters.write_errors.load(Ordering::Relaxed), 2);
+        assert_eq!(writer_counters.flush_errors.load(Ordering::Relaxed), 1);
+    }
+}
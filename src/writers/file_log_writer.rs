@@ -1,49 +1,99 @@
+use error_handler::ErrorSink;
 use formats::default_format;
-use log::Record;
+use formatter::Formatter;
+use log::{Level, Record};
+use panic_hook::in_panic_hook;
+use statistics::FileStatistics;
+use writers::background_writer::{BackgroundWriter, LineSink, OverflowPolicy};
 use writers::log_writer::LogWriter;
 use FlexiLoggerError;
 use FormatFunction;
 
-use chrono::Local;
+use chrono::{self, DateTime, Local, Timelike};
 use glob::glob;
 use std::cell::RefCell;
-use std::cmp::max;
+use std::cmp::{max, Reverse};
+use std::collections::HashMap;
 use std::env;
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, BufRead, BufReader, LineWriter, Write};
 use std::ops::{Add, DerefMut};
 use std::path::Path;
-use std::sync::Mutex;
-use glob::Paths;
-use glob::PatternError;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::vec::Vec;
 use std::path::PathBuf;
+#[cfg(feature = "compress")]
+use std::mem;
+#[cfg(feature = "compress")]
+use std::thread;
+#[cfg(feature = "compress")]
+use flate2::write::GzEncoder;
+#[cfg(feature = "compress")]
+use flate2::Compression;
+
+// The suffix that is appended to the names of compressed log files.
+const COMPRESSED_SUFFIX: &str = ".gz";
+
+// The initial capacity of the buffers for formatting log lines;
+// a thread's buffer is reset if an exceptionally long log line made it grow beyond the maximum.
+const DEFAULT_BUFFER_CAPACITY: usize = 200;
+const MAX_BUFFER_CAPACITY: usize = 8 * 1024;
+
+// How often a FileLogWriter checks whether its file was moved or deleted by someone else.
+const EXTERNAL_ROTATION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+// How often a FileLogWriter tries to open its file again while writing to it fails.
+const RECOVERY_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+thread_local! {
+    static BUFFER: RefCell<Vec<u8>> = RefCell::new(Vec::with_capacity(DEFAULT_BUFFER_CAPACITY));
+}
 
 // The immutable configuration of a FileLogWriter.
+#[derive(Clone)]
 struct FileLogWriterConfig {
-    format: FormatFunction,
+    format: Formatter,
     print_message: bool,
     filename_base: Option<String>,
+    // the part of filename_base that the files of all runs of the program have in common
+    filename_prefix: Option<String>,
     suffix: String,
     use_timestamp: bool,
     append: bool,
     rotate_over_size: Option<u64>,
+    rotate_over_age: Option<Age>,
     max_backup: Option<u16>,
+    max_file_age: Option<Duration>,
+    max_total_size: Option<u64>,
+    #[cfg(feature = "compress")]
+    compress: bool,
     create_symlink: Option<String>,
+    reopen_on_external_rotation: bool,
+    recovery_buffer_size: Option<usize>,
 }
 impl FileLogWriterConfig {
     // Factory method; uses the same defaults as Logger.
     pub fn default() -> FileLogWriterConfig {
         FileLogWriterConfig {
-            format: default_format,
+            format: Formatter::Function(default_format),
             print_message: false,
             filename_base: None,
+            filename_prefix: None,
             suffix: "log".to_string(),
             use_timestamp: true,
             append: false,
             max_backup: None,
+            max_file_age: None,
+            max_total_size: None,
             rotate_over_size: None,
+            rotate_over_age: None,
+            #[cfg(feature = "compress")]
+            compress: false,
             create_symlink: None,
+            reopen_on_external_rotation: false,
+            recovery_buffer_size: None,
         }
     }
 
@@ -55,6 +105,7 @@ impl FileLogWriterConfig {
             if let Some(discriminant) = o_discriminant {
                 filename = filename.add(&format!("_{}", discriminant));
             }
+            self.filename_prefix = Some(filename.clone());
             if self.use_timestamp {
                 filename = filename.add(&Local::now().format("_%Y-%m-%d_%H-%M-%S").to_string())
             };
@@ -62,19 +113,134 @@ impl FileLogWriterConfig {
         }
     }
 
-    fn get_filename(&self, rotate_idx: u32) -> String {
+    // The filename base, extended with the tag of the given period, if any.
+    fn get_period_base(&self, period: Option<&String>) -> String {
         let mut filename = String::with_capacity(180).add(self.filename_base.as_ref().unwrap());
+        if let Some(period) = period {
+            filename = filename.add("_").add(period);
+        }
+        filename
+    }
+
+    fn get_filename(&self, period: Option<&String>, rotate_idx: u32) -> String {
+        let mut filename = self.get_period_base(period);
         if self.rotate_over_size.is_some() {
             filename = filename.add(&format!("_r{:0>5}", rotate_idx))
         };
         filename.add(".").add(&self.suffix)
     }
+
+    // The first rotate index to use for the given period.
+    fn get_initial_rotate_idx(&self, period: Option<&String>) -> u32 {
+        match self.rotate_over_size {
+            None => 0,
+            Some(_) => {
+                let rotate_idx = get_highest_rotate_idx(&self.get_period_base(period), &self.suffix);
+                if self.append {
+                    rotate_idx
+                } else {
+                    rotate_idx + 1
+                }
+            }
+        }
+    }
+
+    // Whether existing files are continued rather than truncated; files that are named
+    // only by their period are always continued, otherwise restarting the program within
+    // the period would wipe the log of the period.
+    fn continues_files(&self) -> bool {
+        self.append || (self.rotate_over_age.is_some() && self.rotate_over_size.is_none())
+    }
+
+    // The glob pattern infix that matches the names of the files of this and former runs
+    // of the program, as they are named with this configuration.
+    fn get_run_infix(&self) -> String {
+        const DATE: &str = "_[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]";
+        const HOUR: &str = "_[0-9][0-9]";
+        const TIME: &str = "_[0-9][0-9]-[0-9][0-9]-[0-9][0-9]";
+        let mut infix = String::new();
+        if self.use_timestamp {
+            infix = infix.add(DATE).add(TIME);
+        }
+        match self.rotate_over_age {
+            Some(Age::Hour) => infix = infix.add(DATE).add(HOUR),
+            Some(Age::Day) => infix = infix.add(DATE),
+            Some(Age::Every(_)) => infix = infix.add(DATE).add(TIME),
+            None => {}
+        }
+        if self.rotate_over_size.is_some() {
+            infix = infix.add("_r[0-9][0-9][0-9][0-9][0-9]");
+        }
+        infix
+    }
+
+    // The glob pattern infix that matches the names of all rotated files.
+    fn get_rotation_infix(&self) -> &'static str {
+        if self.rotate_over_age.is_some() {
+            "_[0-9]*"
+        } else {
+            "_r*"
+        }
+    }
+}
+
+/// Age-based criterion for rotating the log files of a `FileLogWriter`.
+///
+/// See [`FileLogWriterBuilder::rotate_over_age()`](struct.FileLogWriterBuilder.html#method.rotate_over_age).
+#[derive(Clone, Copy, Debug)]
+pub enum Age {
+    /// A new file is started whenever the hour changes.
+    ///
+    /// The file names contain the period like in `myprog_2018-09-24_13.log`.
+    Hour,
+    /// A new file is started whenever the calendar day changes.
+    ///
+    /// The file names contain the period like in `myprog_2018-09-24.log`.
+    Day,
+    /// A new file is started whenever the given duration has passed since the current
+    /// file was opened.
+    ///
+    /// The file names contain the start of the period like in `myprog_2018-09-24_13-25-01.log`,
+    /// so the duration should not be shorter than a second.
+    Every(Duration),
+}
+impl Age {
+    // The tag that is used in the file names for the period that contains `start`.
+    fn get_period_tag(&self, start: &DateTime<Local>) -> String {
+        match *self {
+            Age::Hour => start.format("%Y-%m-%d_%H"),
+            Age::Day => start.format("%Y-%m-%d"),
+            Age::Every(_) => start.format("%Y-%m-%d_%H-%M-%S"),
+        }.to_string()
+    }
+
+    // The end of the period that contains `start`; None if the period never ends.
+    fn get_period_end(&self, start: &DateTime<Local>) -> Option<DateTime<Local>> {
+        let full_hour = start
+            .with_minute(0)
+            .and_then(|t| t.with_second(0))
+            .and_then(|t| t.with_nanosecond(0))
+            .unwrap_or(*start);
+        match *self {
+            Age::Hour => full_hour.checked_add_signed(chrono::Duration::hours(1)),
+            Age::Day => full_hour
+                .with_hour(0)
+                .unwrap_or(full_hour)
+                .checked_add_signed(chrono::Duration::days(1)),
+            Age::Every(duration) => chrono::Duration::from_std(duration)
+                .ok()
+                .and_then(|duration| start.checked_add_signed(duration)),
+        }
+    }
 }
 
 /// Builder for `FileLogWriter`.
+#[derive(Clone)]
 pub struct FileLogWriterBuilder {
     directory: Option<String>,
     discriminant: Option<String>,
+    async_mode: Option<(usize, OverflowPolicy)>,
+    format_pattern: Option<String>,
     config: FileLogWriterConfig,
 }
 
@@ -90,7 +256,18 @@ impl FileLogWriterBuilder {
     /// Makes the `FileLogWriter` use the provided format function for the log entries,
     /// rather than the default ([formats::default_format](fn.default_format.html)).
     pub fn format(mut self, format: FormatFunction) -> FileLogWriterBuilder {
-        self.config.format = format;
+        self.config.format = Formatter::Function(format);
+        self.format_pattern = None;
+        self
+    }
+
+    /// Makes the `FileLogWriter` format the log entries according to the given pattern,
+    /// rather than with a format function.
+    ///
+    /// See [`Logger::format_pattern()`](../struct.Logger.html#method.format_pattern)
+    /// for the syntax. The pattern is validated in `instantiate()`.
+    pub fn format_pattern<S: Into<String>>(mut self, pattern: S) -> FileLogWriterBuilder {
+        self.format_pattern = Some(pattern.into());
         self
     }
 
@@ -126,6 +303,22 @@ impl FileLogWriterBuilder {
         self
     }
 
+    /// By default, the log file will grow indefinitely.
+    /// With this option, a new file is opened whenever the given `Age` is reached,
+    /// e.g. every hour or at the start of every day.
+    /// Also the filename pattern changes - instead of the timestamp,
+    /// the period is included into the filename.
+    ///
+    /// If the program is restarted within a period, it continues the file of the period.
+    ///
+    /// This option can be combined with `rotate_over_size()`; then the files of each period
+    /// are additionally rotated by size, and numbered within the period.
+    pub fn rotate_over_age(mut self, age: Age) -> FileLogWriterBuilder {
+        self.config.rotate_over_age = Some(age);
+        self.config.use_timestamp = false;
+        self
+    }
+
     /// By default, if there is a rotate_over_size defined the count of backup file
     /// will grow indefinitely. If a max_backup is set, the count of backuo file will
     /// be limited to max value
@@ -134,6 +327,46 @@ impl FileLogWriterBuilder {
         self
     }
 
+    /// Makes the `FileLogWriter` delete log files that were last modified longer ago
+    /// than the given duration.
+    ///
+    /// Unlike `max_backup()`, this applies to the log files of all former runs of the program,
+    /// i.e., to all files in the directory whose names consist of the program name
+    /// (and the discriminant, if any), and the timestamp, period, or rotation index
+    /// as the current configuration produces them, compressed or not.
+    /// The current log file, and files that are still being compressed, are never deleted.
+    ///
+    /// The retention is evaluated at startup and after each rotation.
+    pub fn max_file_age(mut self, max_file_age: Duration) -> FileLogWriterBuilder {
+        self.config.max_file_age = Some(max_file_age);
+        self
+    }
+
+    /// Makes the `FileLogWriter` delete the oldest log files when the log files together
+    /// exceed the given number of bytes.
+    ///
+    /// The same files are considered as with [`max_file_age()`](#method.max_file_age),
+    /// the size of the current log file is included in the total.
+    ///
+    /// The retention is evaluated at startup and after each rotation.
+    pub fn max_total_size(mut self, max_total_size: usize) -> FileLogWriterBuilder {
+        self.config.max_total_size = Some(max_total_size as u64);
+        self
+    }
+
+    /// Makes the `FileLogWriter` compress each log file with gzip when it is finished,
+    /// i.e., when rotation switches to the next file.
+    ///
+    /// The compression runs in a background thread, the compressed files get the additional
+    /// suffix `.gz`. This option only has an effect if rotation is used.
+    ///
+    /// This method is only available with the `compress` feature.
+    #[cfg(feature = "compress")]
+    pub fn compress(mut self) -> FileLogWriterBuilder {
+        self.config.compress = true;
+        self
+    }
+
     /// Makes the logger append to the given file, if it exists; by default, the file would be
     /// truncated.
     pub fn append(mut self) -> FileLogWriterBuilder {
@@ -154,8 +387,68 @@ impl FileLogWriterBuilder {
         self
     }
 
+    /// Makes the `FileLogWriter` detect when its current file was renamed or deleted
+    /// by someone else, e.g. by `logrotate`, and then reopen the file under its original path.
+    ///
+    /// The check is done at most once per second, during a log call.
+    /// Alternatively, the file can be reopened explicitly with
+    /// [`FileLogWriter::reopen_output_file()`](struct.FileLogWriter.html#method.reopen_output_file).
+    pub fn reopen_on_external_rotation(mut self) -> FileLogWriterBuilder {
+        self.config.reopen_on_external_rotation = true;
+        self
+    }
+
+    /// Makes the `FileLogWriter` recover from failures of writing to its file,
+    /// e.g. when the disk is full or the directory was removed.
+    ///
+    /// While writing fails, the log lines are kept in memory, up to the given number of bytes;
+    /// further log lines are written to stderr instead.
+    /// About once per second, during a log call, and when it is flushed, the `FileLogWriter`
+    /// tries to open its current file again, recreating the directory if necessary.
+    /// When this succeeds, it writes a line that describes the gap, and then the log lines
+    /// that were kept in memory.
+    ///
+    /// The start of a failure is reported once, as a write error, and is counted in the
+    /// [`Statistics`](../struct.Statistics.html) of the logger; the log calls themselves
+    /// succeed while the log lines are kept.
+    ///
+    /// Note that writing to a file whose directory was removed still succeeds on most
+    /// platforms, so such a failure is only detected when the file is rotated or reopened, see
+    /// [`reopen_on_external_rotation()`](#method.reopen_on_external_rotation).
+    /// Without this option, the errors are passed on to the caller.
+    pub fn recover_from_io_errors(mut self, buffer_size: usize) -> FileLogWriterBuilder {
+        self.config.recovery_buffer_size = Some(buffer_size);
+        self
+    }
+
+    /// Makes the `FileLogWriter` write in the background.
+    ///
+    /// The log lines are formatted in the calling thread and then handed
+    /// through a queue with the given capacity to a dedicated thread, which writes them
+    /// to the file. The `OverflowPolicy` defines what happens if the queue is full.
+    ///
+    /// Flushing the `FileLogWriter` waits until all log lines that were handed over
+    /// before are written; dropping it writes all pending log lines.
+    /// Since the global logger is never dropped, you should call `log::logger().flush()`
+    /// before your program exits, to make sure that no log lines get lost.
+    ///
+    /// If the background thread ends unexpectedly, the log lines are written
+    /// in the calling thread again.
+    pub fn async_mode(
+        mut self,
+        capacity: usize,
+        overflow: OverflowPolicy,
+    ) -> FileLogWriterBuilder {
+        self.async_mode = Some((capacity, overflow));
+        self
+    }
+
     /// Produces the FileLogWriter.
     pub fn instantiate(mut self) -> Result<FileLogWriter, FlexiLoggerError> {
+        if let Some(ref pattern) = self.format_pattern {
+            self.config.format = Formatter::parse_pattern(pattern)?;
+        }
+
         // make sure the folder exists or create it
         let s_directory: String = self.directory.unwrap_or_else(|| ".".to_string());
         let p_directory = Path::new(&s_directory);
@@ -166,9 +459,32 @@ impl FileLogWriterBuilder {
 
         self.config
             .set_filename_base(&s_directory, self.discriminant);
+        let config = Arc::new(self.config);
+        let counters = Arc::new(FileCounters::default());
+        let error_sink = ErrorSink::default();
+        let state = Arc::new(Mutex::new(RefCell::new(FileLogWriterState::new(
+            &config,
+            Arc::clone(&counters),
+        )?)));
+        let o_background_writer = match self.async_mode {
+            None => None,
+            Some((capacity, overflow)) => Some(BackgroundWriter::new(
+                capacity,
+                overflow,
+                BackgroundSink {
+                    config: Arc::clone(&config),
+                    state: Arc::clone(&state),
+                    error_sink: error_sink.clone(),
+                },
+                error_sink.clone(),
+            )?),
+        };
         Ok(FileLogWriter {
-            state: Mutex::new(RefCell::new(FileLogWriterState::new(&self.config)?)),
-            config: self.config,
+            config,
+            state,
+            counters,
+            error_sink,
+            o_background_writer,
         })
     }
 }
@@ -184,6 +500,16 @@ impl FileLogWriterBuilder {
         self
     }
 
+    /// With Some, makes the `FileLogWriter` format the log entries according to the given
+    /// pattern; with None, the format function is used.
+    pub fn o_format_pattern<S: Into<String>>(
+        mut self,
+        pattern: Option<S>,
+    ) -> FileLogWriterBuilder {
+        self.format_pattern = pattern.map(|p| p.into());
+        self
+    }
+
     /// Specifies a folder for the log files.
     ///
     /// If the specified folder does not exist, the initialization will fail.
@@ -210,6 +536,18 @@ impl FileLogWriterBuilder {
         self
     }
 
+    /// By default, and with None, the log file will grow indefinitely.
+    /// If an `Age` is set, a new file is opened whenever the age is reached.
+    /// Also the filename pattern changes - instead of the timestamp the period
+    /// is included into the filename.
+    pub fn o_rotate_over_age(mut self, age: Option<Age>) -> FileLogWriterBuilder {
+        if age.is_some() {
+            self.config.use_timestamp = false;
+        }
+        self.config.rotate_over_age = age;
+        self
+    }
+
     /// By default, if there is a rotate_over_size defined the count of backup file
     /// will grow indefinitely. If a max_backup is set, the count of backuo file will
     /// be limited to max value
@@ -218,6 +556,56 @@ impl FileLogWriterBuilder {
         self
     }
 
+    /// With Some, makes the `FileLogWriter` delete log files that were last modified
+    /// longer ago than the given duration, see [`max_file_age()`](#method.max_file_age).
+    pub fn o_max_file_age(mut self, max_file_age: Option<Duration>) -> FileLogWriterBuilder {
+        self.config.max_file_age = max_file_age;
+        self
+    }
+
+    /// With Some, makes the `FileLogWriter` delete the oldest log files when the log files
+    /// together exceed the given number of bytes, see [`max_total_size()`](#method.max_total_size).
+    pub fn o_max_total_size(mut self, max_total_size: Option<usize>) -> FileLogWriterBuilder {
+        self.config.max_total_size = max_total_size.map(|s| s as u64);
+        self
+    }
+
+    /// With true, makes the `FileLogWriter` compress each log file with gzip when it is finished.
+    ///
+    /// This method is only available with the `compress` feature.
+    #[cfg(feature = "compress")]
+    pub fn o_compress(mut self, compress: bool) -> FileLogWriterBuilder {
+        self.config.compress = compress;
+        self
+    }
+
+    /// With true, makes the `FileLogWriter` reopen its file when it was renamed or deleted
+    /// by someone else, see [`reopen_on_external_rotation()`](#method.reopen_on_external_rotation).
+    pub fn o_reopen_on_external_rotation(
+        mut self,
+        reopen_on_external_rotation: bool,
+    ) -> FileLogWriterBuilder {
+        self.config.reopen_on_external_rotation = reopen_on_external_rotation;
+        self
+    }
+
+    /// With `Some(buffer_size)`, makes the `FileLogWriter` recover from failures of writing
+    /// to its file, see [`recover_from_io_errors()`](#method.recover_from_io_errors).
+    pub fn o_recover_from_io_errors(mut self, buffer_size: Option<usize>) -> FileLogWriterBuilder {
+        self.config.recovery_buffer_size = buffer_size;
+        self
+    }
+
+    /// With `Some((capacity, overflow))`, makes the `FileLogWriter` write in the background;
+    /// see [`async_mode()`](#method.async_mode).
+    pub fn o_async_mode(
+        mut self,
+        async_mode: Option<(usize, OverflowPolicy)>,
+    ) -> FileLogWriterBuilder {
+        self.async_mode = async_mode;
+        self
+    }
+
     /// If append is set to true, makes the logger append to the given file, if it exists.
     /// By default, or with false, the file would be truncated.
     pub fn o_append(mut self, append: bool) -> FileLogWriterBuilder {
@@ -242,74 +630,345 @@ impl FileLogWriterBuilder {
     }
 }
 
+// The counters of a FileLogWriter.
+#[derive(Default)]
+struct FileCounters {
+    bytes_written: AtomicU64,
+    rotations: AtomicU64,
+}
+
 // The mutable state of a FileLogWriter.
 struct FileLogWriterState {
     lw: LineWriter<File>,
     rotate_over: bool,
     written_bytes: u64,
     rotate_idx: u32,
+    period: Option<String>,
+    period_end: Option<DateTime<Local>>,
     current_path: String,
+    // identifies the open file, for detecting when current_path refers to another file
+    o_file_id: Option<(u64, u64)>,
+    last_external_rotation_check: Instant,
+    counters: Arc<FileCounters>,
+    o_recovery_buffer_size: Option<usize>,
+    o_failure: Option<Failure>,
+    // the start of a failure, which is reported after the lock on the state is released
+    o_unreported: Option<io::Error>,
+}
+
+// The log lines that could not be written to the file, while writing to it fails.
+struct Failure {
+    error: String,
+    since: DateTime<Local>,
+    last_retry: Instant,
+    buffer: Vec<u8>,
+    buffered_lines: usize,
+    diverted_lines: usize,
+}
+impl Failure {
+    // Formats the line that describes the gap in the file.
+    fn format_marker(&self, buffer: &mut Vec<u8>, config: &FileLogWriterConfig) -> io::Result<()> {
+        let mut message = format!(
+            "writing to this file failed from {} to {} with \"{}\"; \
+             the {} log lines of that time that were kept in memory follow",
+            self.since.format("%Y-%m-%d %H:%M:%S%.6f"),
+            Local::now().format("%Y-%m-%d %H:%M:%S%.6f"),
+            self.error,
+            self.buffered_lines
+        );
+        if self.diverted_lines > 0 {
+            message.push_str(&format!(
+                ", {} log lines were written to stderr instead",
+                self.diverted_lines
+            ));
+        }
+        config.format.format(
+            buffer,
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .level(Level::Warn)
+                .target("flexi_logger")
+                .module_path(Some("flexi_logger"))
+                .build(),
+        )?;
+        buffer.push(b'\n');
+        Ok(())
+    }
 }
 impl FileLogWriterState {
-    fn new(config: &FileLogWriterConfig) -> Result<FileLogWriterState, FlexiLoggerError> {
-        let rotate_idx = match config.rotate_over_size {
-            None => 0,
-            Some(_) => {
-                let rotate_idx =
-                    get_highest_rotate_idx(config.filename_base.as_ref().unwrap(), &config.suffix);
-                if config.append {
-                    rotate_idx
-                } else {
-                    rotate_idx + 1
-                }
-            }
-        };
+    fn new(
+        config: &FileLogWriterConfig,
+        counters: Arc<FileCounters>,
+    ) -> Result<FileLogWriterState, FlexiLoggerError> {
+        let now = Local::now();
+        let period = config.rotate_over_age.map(|age| age.get_period_tag(&now));
+        let period_end = config
+            .rotate_over_age
+            .and_then(|age| age.get_period_end(&now));
+        let rotate_idx = config.get_initial_rotate_idx(period.as_ref());
 
-        let (lw, written_bytes, current_path) = get_linewriter(rotate_idx, config)?;
+        let (lw, written_bytes, current_path) =
+            get_linewriter(period.as_ref(), rotate_idx, config)?;
+        with_files_locked(config, || remove_outdated_files(config, &current_path));
+        let o_file_id = platform::file_id(&lw.get_ref().metadata()?);
         Ok(FileLogWriterState {
+            counters,
+            o_recovery_buffer_size: config.recovery_buffer_size,
+            o_failure: None,
+            o_unreported: None,
+            o_file_id,
+            last_external_rotation_check: Instant::now(),
             lw,
             current_path,
             written_bytes,
             rotate_idx,
+            period,
+            period_end,
             rotate_over: config.rotate_over_size.is_some(),
         })
     }
 
+    // Checks whether the current file is full or its period is over.
+    fn must_rotate(&self, config: &FileLogWriterConfig) -> bool {
+        if let Some(rotate_over_size) = config.rotate_over_size {
+            if self.written_bytes > rotate_over_size {
+                return true;
+            }
+        }
+        if let Some(period_end) = self.period_end {
+            if Local::now() >= period_end {
+                return true;
+            }
+        }
+        false
+    }
+
+    // Checks whether the current file was moved or deleted by someone else.
+    fn is_externally_rotated(&self) -> bool {
+        match fs::metadata(&self.current_path) {
+            Err(_) => true,
+            Ok(metadata) => match (self.o_file_id, platform::file_id(&metadata)) {
+                (Some(open_id), Some(path_id)) => open_id != path_id,
+                _ => false,
+            },
+        }
+    }
+
+    // Opens the file with the current path again, after flushing the previously open file.
+    fn reopen(&mut self) -> io::Result<()> {
+        self.lw.flush()?;
+        self.open_current_path()
+    }
+
+    fn open_current_path(&mut self) -> io::Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.current_path)?;
+        let metadata = file.metadata()?;
+        self.written_bytes = metadata.len();
+        self.o_file_id = platform::file_id(&metadata);
+        self.lw = LineWriter::new(file);
+        Ok(())
+    }
+
+    // Switches to the next file if necessary, and removes the files that exceed max_backup
+    // or the retention limits.
+    fn rotate_if_necessary(&mut self, config: &FileLogWriterConfig) {
+        if self.o_failure.is_some() {
+            self.retry(config, false);
+            if self.o_failure.is_some() {
+                return;
+            }
+        }
+        if config.reopen_on_external_rotation
+            && self.last_external_rotation_check.elapsed() >= EXTERNAL_ROTATION_CHECK_INTERVAL
+        {
+            self.last_external_rotation_check = Instant::now();
+            if self.is_externally_rotated() {
+                if let Err(e) = self.reopen() {
+                    if self.o_recovery_buffer_size.is_some() {
+                        self.fail(e.to_string());
+                        return;
+                    }
+                    eprintln!("FlexiLogger: reopening file failed with {}", e);
+                }
+            }
+        }
+        if !self.must_rotate(config) {
+            return;
+        }
+        match self.mount_next_linewriter(config) {
+            Ok(compressing) => {
+                self.counters.rotations.fetch_add(1, Ordering::Relaxed);
+                if compressing {
+                    // the compression thread removes the old files when it is done
+                    return;
+                }
+            }
+            Err(e) => {
+                if self.o_recovery_buffer_size.is_some() {
+                    self.fail(e.to_string());
+                    return;
+                }
+                eprintln!("FlexiLogger: opening file failed with {}", e);
+            }
+        }
+
+        with_files_locked(config, || remove_old_files(config, &self.current_path));
+    }
+
+    // Starts keeping the log lines in memory; the failure is reported once, as a write error.
+    fn fail(&mut self, error: String) {
+        self.o_unreported = Some(io::Error::new(
+            io::ErrorKind::Other,
+            format!(
+                "{}; log lines are kept in memory until writing to {} succeeds again",
+                error, self.current_path
+            ),
+        ));
+        self.o_failure = Some(Failure {
+            error,
+            since: Local::now(),
+            last_retry: Instant::now(),
+            buffer: Vec::new(),
+            buffered_lines: 0,
+            diverted_lines: 0,
+        });
+    }
+
+    // Keeps the log lines in memory, or writes them to stderr if the buffer is full.
+    fn keep(&mut self, buf: &[u8]) {
+        let buffer_size = self.o_recovery_buffer_size.unwrap_or(0);
+        if let Some(ref mut failure) = self.o_failure {
+            let lines = buf.iter().filter(|&&b| b == b'\n').count();
+            if failure.buffer.len() + buf.len() <= buffer_size {
+                failure.buffer.extend_from_slice(buf);
+                failure.buffered_lines += lines;
+            } else {
+                io::stderr().write_all(buf).ok();
+                failure.diverted_lines += lines;
+            }
+        }
+    }
+
+    // Tries to open the current file again, recreating its directory, and to write
+    // the marker line and the kept log lines to it; without force, only if a retry is due.
+    fn retry(&mut self, config: &FileLogWriterConfig, force: bool) {
+        let mut lines = Vec::<u8>::new();
+        match self.o_failure {
+            Some(ref failure)
+                if force || failure.last_retry.elapsed() >= RECOVERY_RETRY_INTERVAL =>
+            {
+                if let Err(e) = failure.format_marker(&mut lines, config) {
+                    eprintln!("FlexiLogger: formatting the marker line failed with {}", e);
+                }
+                lines.extend_from_slice(&failure.buffer);
+            }
+            _ => return,
+        }
+        let result = match Path::new(&self.current_path).parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => fs::create_dir_all(dir),
+            _ => Ok(()),
+        }
+        .and_then(|()| self.open_current_path())
+        .and_then(|()| self.lw.write_all(&lines));
+        match result {
+            Ok(()) => {
+                self.o_failure = None;
+                self.count_written(lines.len());
+            }
+            Err(_) => {
+                if let Some(ref mut failure) = self.o_failure {
+                    failure.last_retry = Instant::now();
+                }
+            }
+        }
+    }
+
+    fn count_written(&mut self, len: usize) {
+        self.counters
+            .bytes_written
+            .fetch_add(len as u64, Ordering::Relaxed);
+        if self.rotate_over {
+            self.written_bytes += len as u64;
+        };
+    }
+
+    // Opens the next file; returns true if the finished file is compressed in the background.
     fn mount_next_linewriter(
         &mut self,
         config: &FileLogWriterConfig,
-    ) -> Result<(), FlexiLoggerError> {
-        self.rotate_idx += 1;
+    ) -> Result<bool, FlexiLoggerError> {
+        let now = Local::now();
+        match (config.rotate_over_age, self.period_end) {
+            (Some(age), Some(period_end)) if now >= period_end => {
+                self.period = Some(age.get_period_tag(&now));
+                self.period_end = age.get_period_end(&now);
+                self.rotate_idx = config.get_initial_rotate_idx(self.period.as_ref());
+            }
+            _ => self.rotate_idx += 1,
+        }
         self.written_bytes = 0;
-        let (lw, wb, cp) = get_linewriter(self.rotate_idx, config)?;
+        let (lw, wb, cp) = get_linewriter(self.period.as_ref(), self.rotate_idx, config)?;
+        // replacing the LineWriter flushes and closes the finished file
+        self.o_file_id = platform::file_id(&lw.get_ref().metadata()?);
         self.lw = lw;
         self.written_bytes = wb;
-        self.current_path = cp;
-        Ok(())
+        #[cfg(feature = "compress")]
+        {
+            let finished_path = mem::replace(&mut self.current_path, cp);
+            if config.compress && finished_path != self.current_path {
+                compress_in_background(finished_path, config.clone(), self.current_path.clone());
+                return Ok(true);
+            }
+        }
+        #[cfg(not(feature = "compress"))]
+        {
+            self.current_path = cp;
+        }
+        Ok(false)
     }
 }
 
 impl Write for FileLogWriterState {
     #[inline]
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.lw.write_all(buf)?;
-        if self.rotate_over {
-            self.written_bytes += buf.len() as u64;
-        };
+        if self.o_failure.is_none() {
+            match self.lw.write_all(buf) {
+                Ok(()) => {
+                    self.count_written(buf.len());
+                    return Ok(buf.len());
+                }
+                Err(e) => {
+                    if self.o_recovery_buffer_size.is_none() {
+                        return Err(e);
+                    }
+                    self.fail(e.to_string());
+                }
+            }
+        }
+        self.keep(buf);
         Ok(buf.len())
     }
 
     #[inline]
     fn flush(&mut self) -> io::Result<()> {
+        if self.o_failure.is_some() {
+            // the kept log lines are written when writing succeeds again
+            return Ok(());
+        }
         self.lw.flush()
     }
 }
 
 fn get_linewriter(
+    period: Option<&String>,
     rotate_idx: u32,
     config: &FileLogWriterConfig,
 ) -> Result<(LineWriter<File>, u64, String), FlexiLoggerError> {
-    let filename = config.get_filename(rotate_idx);
+    let filename = config.get_filename(period, rotate_idx);
+    let append = config.continues_files();
     let (lw, wb) = {
         let path = Path::new(&filename);
         if config.print_message {
@@ -323,11 +982,11 @@ fn get_linewriter(
                 OpenOptions::new()
                     .write(true)
                     .create(true)
-                    .append(config.append)
-                    .truncate(!config.append)
+                    .append(append)
+                    .truncate(!append)
                     .open(&path)?,
             ),
-            if config.append {
+            if append {
                 let metadata = fs::metadata(&filename)?;
                 metadata.len()
             } else {
@@ -340,47 +999,207 @@ fn get_linewriter(
 
 fn get_highest_rotate_idx(filename_base: &str, suffix: &str) -> u32 {
     let mut rotate_idx = 0;
-    match list_log_files(filename_base, suffix) {
-        Err(_e) => {}
-        Ok(globresults) => for globresult in globresults {
-            match globresult {
-                Err(e) => eprintln!(
-                    "Error occured when reading directory for log files: {:?}",
-                    e
-                ),
-                Ok(pathbuf) => {
-                    let filename = pathbuf.file_stem().unwrap().to_string_lossy();
-                    let mut it = filename.rsplit("_r");
-                    let idx: u32 = it.next().unwrap().parse().unwrap_or(0);
-                    rotate_idx = max(rotate_idx, idx);
-                }
-            }
-        },
+    for pathbuf in list_log_files(filename_base, "_r*", suffix) {
+        let filename = pathbuf.file_name().unwrap().to_string_lossy();
+        let mut it = filename.rsplit("_r");
+        // cut off the suffix, which may be extended by the compression suffix
+        let idx: u32 = it.next().unwrap().split('.').next().unwrap().parse().unwrap_or(0);
+        rotate_idx = max(rotate_idx, idx);
     }
-
     rotate_idx
 }
 
-fn list_log_files(filename_base: &str, suffix: &str) -> Result<Paths, PatternError> {
+// Lists the log files with the given infix, plain and compressed.
+fn list_log_files(filename_base: &str, infix: &str, suffix: &str) -> Vec<PathBuf> {
     let fn_pattern = String::with_capacity(180)
         .add(filename_base)
-        .add("_r*")
+        .add(infix)
         .add(".")
         .add(suffix);
-    glob(&fn_pattern).or_else(|error| {
-        eprintln!("Listing files with ({}) failed with {}", fn_pattern, error);
-        Err(error)
+    let mut log_files = Vec::<PathBuf>::new();
+    for pattern in &[fn_pattern.clone(), fn_pattern.add(COMPRESSED_SUFFIX)] {
+        match glob(pattern) {
+            Err(e) => eprintln!("Listing files with ({}) failed with {}", pattern, e),
+            Ok(globresults) => for globresult in globresults {
+                match globresult {
+                    Err(e) => eprintln!(
+                        "Error occured when reading directory for log files: {:?}",
+                        e
+                    ),
+                    Ok(pathbuf) => log_files.push(pathbuf),
+                }
+            },
+        }
+    }
+    log_files
+}
+
+lazy_static! {
+    // The locks that serialize the compression and the removal of log files, by file name
+    // prefix; FileLogWriters with the same prefix, e.g. the one that replaces the other
+    // when the output of the logger is reconfigured, share the lock.
+    static ref FILES_LOCKS: Mutex<HashMap<String, Arc<Mutex<()>>>> = Mutex::new(HashMap::new());
+}
+
+// Runs the function while holding the lock for the log files of the configuration.
+fn with_files_locked<R, F: FnOnce() -> R>(config: &FileLogWriterConfig, f: F) -> R {
+    let files_lock = Arc::clone(
+        FILES_LOCKS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(config.filename_prefix.clone().unwrap_or_default())
+            .or_insert_with(|| Arc::new(Mutex::new(()))),
+    );
+    let _guard = files_lock.lock().unwrap_or_else(PoisonError::into_inner);
+    f()
+}
+
+// Removes the files that exceed max_backup, max_file_age, or max_total_size.
+fn remove_old_files(config: &FileLogWriterConfig, current_path: &str) {
+    if let Some(max_backup) = config.max_backup {
+        remove_surplus_backups(config, max_backup);
+    }
+    remove_outdated_files(config, current_path);
+}
+
+// Removes the oldest rotated files, such that at most max_backup remain.
+fn remove_surplus_backups(config: &FileLogWriterConfig, max_backup: u16) {
+    let mut files_path = list_log_files(
+        config.filename_base.as_ref().unwrap(),
+        config.get_rotation_infix(),
+        &config.suffix,
+    );
+    files_path.sort_by(|a, b| b.cmp(a));
+
+    // a file that is still being compressed exists both plain and compressed,
+    // so each file is counted by its name without the compression suffix
+    let mut kept = 0;
+    let mut last_name = String::new();
+    for path in files_path {
+        let path_str = path.to_string_lossy().into_owned();
+        let name = path_str.trim_end_matches(COMPRESSED_SUFFIX);
+        if name != last_name {
+            kept += 1;
+            last_name = name.to_string();
+        }
+        if kept > max_backup {
+            fs::remove_file(&path).ok();
+        }
+    }
+}
+
+// Removes the log files of this and former program runs that are older than max_file_age,
+// or that exceed max_total_size, starting with the oldest; the current file is kept.
+fn remove_outdated_files(config: &FileLogWriterConfig, current_path: &str) {
+    if config.max_file_age.is_none() && config.max_total_size.is_none() {
+        return;
+    }
+    let current_name = Path::new(current_path).file_name();
+    let mut files: Vec<(PathBuf, SystemTime, u64)> = list_log_files(
+        config.filename_prefix.as_ref().unwrap(),
+        &config.get_run_infix(),
+        &config.suffix,
+    )
+    .into_iter()
+    .filter(|path| path.file_name() != current_name)
+    .filter(|path| !is_being_compressed(path) && !has_compressed_file(path))
+    .filter_map(|path| {
+        let metadata = fs::metadata(&path).ok()?;
+        let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+        Some((path, modified, metadata.len()))
     })
+    .collect();
+    // newest first
+    files.sort_by_key(|&(_, modified, _)| Reverse(modified));
+
+    let now = SystemTime::now();
+    let mut total_size = fs::metadata(current_path).map(|m| m.len()).unwrap_or(0);
+    for (path, modified, size) in files {
+        let too_old = match (config.max_file_age, now.duration_since(modified)) {
+            (Some(max_file_age), Ok(age)) => age > max_file_age,
+            _ => false,
+        };
+        let too_big = match config.max_total_size {
+            Some(max_total_size) => total_size + size > max_total_size,
+            None => false,
+        };
+        if too_old || too_big {
+            fs::remove_file(&path).unwrap_or_else(|e| {
+                eprintln!(
+                    "FlexiLogger: removing file {} failed with {}",
+                    path.display(),
+                    e
+                );
+            });
+        } else {
+            total_size += size;
+        }
+    }
+}
+
+// Checks whether the path is a compressed file whose plain file still exists,
+// i.e., that is still being written.
+fn is_being_compressed(path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    path.ends_with(COMPRESSED_SUFFIX)
+        && Path::new(path.trim_end_matches(COMPRESSED_SUFFIX)).exists()
+}
+
+// Checks whether the path is a plain file whose compressed file exists,
+// i.e., that is being compressed.
+fn has_compressed_file(path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    !path.ends_with(COMPRESSED_SUFFIX)
+        && Path::new(&String::with_capacity(180).add(&path).add(COMPRESSED_SUFFIX)).exists()
+}
+
+// Compresses the finished log file in a background thread, so that the log call that
+// triggered the rotation is not blocked; then removes the old files.
+// Both happen while holding the lock for the log files, so that no other compression
+// or removal runs concurrently.
+#[cfg(feature = "compress")]
+fn compress_in_background(path: String, config: FileLogWriterConfig, current_path: String) {
+    thread::Builder::new()
+        .name("flexi_logger-compress".to_string())
+        .spawn(move || {
+            with_files_locked(&config, || {
+                compress(&path).unwrap_or_else(|e| {
+                    eprintln!("FlexiLogger: compressing file {} failed with {}", path, e);
+                });
+                remove_old_files(&config, &current_path);
+            })
+        })
+        .map(|_| ())
+        .unwrap_or_else(|e| {
+            eprintln!("FlexiLogger: starting the compression thread failed with {}", e);
+        });
+}
+
+// Replaces the given file with a gzipped copy.
+#[cfg(feature = "compress")]
+fn compress(path: &str) -> io::Result<()> {
+    let mut log_file = File::open(path)?;
+    let mut encoder = GzEncoder::new(
+        File::create(String::with_capacity(180).add(path).add(COMPRESSED_SUFFIX))?,
+        Compression::default(),
+    );
+    io::copy(&mut log_file, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(path)
 }
 
 /// A configurable `LogWriter` that writes to a file or, if rotation is used, a sequence of files.
 pub struct FileLogWriter {
-    config: FileLogWriterConfig,
+    config: Arc<FileLogWriterConfig>,
     // the state needs to be mutable; since `Log.log()` requires an unmutable self,
     // which translates into a non-mutating `LogWriter::write()`,
     // we need the internal mutability of RefCell, and we have to wrap it with a Mutex to be
-    // thread-safe
-    state: Mutex<RefCell<FileLogWriterState>>,
+    // thread-safe; in async mode, the state is shared with the background thread
+    state: Arc<Mutex<RefCell<FileLogWriterState>>>,
+    counters: Arc<FileCounters>,
+    // takes the errors of the background thread
+    error_sink: ErrorSink,
+    o_background_writer: Option<BackgroundWriter>,
 }
 impl FileLogWriter {
     /// Instantiates a builder for `FileLogWriter`.
@@ -388,18 +1207,66 @@ impl FileLogWriter {
         FileLogWriterBuilder {
             directory: None,
             discriminant: None,
+            async_mode: None,
+            format_pattern: None,
             config: FileLogWriterConfig::default(),
         }
     }
 
-    /// Returns a reference to its configuration.
-    pub fn format(&self) -> FormatFunction {
-        self.config.format
+    /// Returns the format function of the `FileLogWriter`,
+    /// or `None` if it uses a format pattern.
+    pub fn format(&self) -> Option<FormatFunction> {
+        match self.config.format {
+            Formatter::Function(f) => Some(f),
+            Formatter::Pattern(_) => None,
+        }
+    }
+
+    pub(crate) fn formatter(&self) -> &Formatter {
+        &self.config.format
+    }
+
+    // Takes the errors that occur outside of write() and flush();
+    // the logger connects it to its error handling.
+    pub(crate) fn error_sink(&self) -> &ErrorSink {
+        &self.error_sink
+    }
+
+    // Writes already formatted log lines, e.g. from a RingBufferWriter.
+    pub(crate) fn write_lines(&self, lines: &[u8]) -> io::Result<()> {
+        if let Some(ref background_writer) = self.o_background_writer {
+            if background_writer.write(lines.to_vec()).is_ok() {
+                return Ok(());
+            }
+        }
+        self.write_directly(lines)
+    }
+
+    /// Returns the counters of the written log files.
+    pub fn statistics(&self) -> FileStatistics {
+        FileStatistics {
+            bytes_written: self.counters.bytes_written.load(Ordering::Relaxed),
+            rotations: self.counters.rotations.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Closes the current file and opens it again under the same path,
+    /// e.g. after it was renamed or deleted by `logrotate`.
+    ///
+    /// If the file was not moved, logging simply continues at its end.
+    pub fn reopen_output_file(&self) -> io::Result<()> {
+        if let Some(ref background_writer) = self.o_background_writer {
+            background_writer.flush();
+        }
+        let guard = self.state.lock().unwrap();
+        let mut state = guard.borrow_mut();
+        state.reopen()
     }
 
     // don't use this function in productive code - it exists only for flexi_loggers own tests
     #[doc(hidden)]
     pub fn validate_logs(&self, expected: &[(&'static str, &'static str, &'static str)]) -> bool {
+        self.flush().unwrap();
         let guard = self.state.lock().unwrap();
         let state = guard.borrow();
         let path = Path::new(&state.current_path);
@@ -430,54 +1297,156 @@ impl FileLogWriter {
     }
 }
 
-impl LogWriter for FileLogWriter {
-    #[inline]
-    fn write(&self, record: &Record) -> io::Result<()> {
-        let guard = self.state.lock().unwrap(); // : MutexGuard<RefCell<FileLogWriterState>>
-        let mut state = guard.borrow_mut(); // : RefMut<FileLogWriterState>
-        let state = state.deref_mut(); // : &mut FileLogWriterState
+impl FileLogWriter {
+    // Writes the lines in the calling thread, also in async mode if the background thread
+    // has ended.
+    fn write_directly(&self, lines: &[u8]) -> io::Result<()> {
+        write_to_state(&self.state, &self.config, &self.error_sink, lines)
+    }
 
-        // switch to next file if necessary
-        if let Some(rotate_over_size) = self.config.rotate_over_size {
-            if state.written_bytes > rotate_over_size {
-                state
-                    .mount_next_linewriter(&self.config)
-                    .unwrap_or_else(|e| {
-                        eprintln!("FlexiLogger: opening file failed with {}", e);
-                    });
+    // Formats the log line into the buffer and then writes it with a single call
+    // while holding the lock.
+    fn format_and_write(&self, buffer: &mut Vec<u8>, record: &Record) -> io::Result<()> {
+        buffer.clear();
+        self.config.format.format(buffer, record)?;
+        buffer.push(b'\n');
 
-                if let Some(max_backup) = self.config.max_backup {
-                    let _ = list_log_files(self.config.filename_base.as_ref().unwrap(), &self.config.suffix)
-                        .map(|globresults| {
-                            let mut files_path = globresults.filter_map(|pathbuf| pathbuf.ok()).collect::<Vec<PathBuf>>();
+        write_to_state(&self.state, &self.config, &self.error_sink, buffer)
+    }
+}
 
-                            files_path.sort_by(|a, b| b.cmp(a));
+// Writes the lines while holding the lock on the state; the start of a failure,
+// if writing fails and is recovered from, is reported after the lock is released,
+// since the error handling may log.
+fn write_to_state(
+    state: &Mutex<RefCell<FileLogWriterState>>,
+    config: &FileLogWriterConfig,
+    error_sink: &ErrorSink,
+    lines: &[u8],
+) -> io::Result<()> {
+    let (result, o_unreported) = {
+        let guard = lock_state(state)?; // : MutexGuard<RefCell<FileLogWriterState>>
+        let mut state = guard.borrow_mut(); // : RefMut<FileLogWriterState>
+        let state = state.deref_mut(); // : &mut FileLogWriterState
 
-                            while files_path.len() as u16 > max_backup {
-                                files_path.pop().map(|file| fs::remove_file(file.as_path()));
+        state.rotate_if_necessary(config);
+        let result = state.write_all(lines);
+        (result, state.o_unreported.take())
+    };
+    if let Some(error) = o_unreported {
+        error_sink.write_failed(error);
+    }
+    result
+}
 
-                            }
-                        });
+// Locks the state; while a panic is logged, the lock is only tried, since the panicking
+// code might hold it, e.g. in validate_logs().
+fn lock_state<'a>(
+    state: &'a Mutex<RefCell<FileLogWriterState>>,
+) -> io::Result<MutexGuard<'a, RefCell<FileLogWriterState>>> {
+    if !in_panic_hook() {
+        return Ok(state.lock().unwrap());
+    }
+    match state.try_lock() {
+        Ok(guard) => Ok(guard),
+        Err(TryLockError::Poisoned(e)) => Ok(e.into_inner()),
+        Err(TryLockError::WouldBlock) => Err(io::Error::new(
+            io::ErrorKind::WouldBlock,
+            "the log file is locked",
+        )),
+    }
+}
 
-                }
-            }
+impl LogWriter for FileLogWriter {
+    #[inline]
+    fn write(&self, record: &Record) -> io::Result<()> {
+        if let Some(ref background_writer) = self.o_background_writer {
+            // format in the calling thread, write in the background
+            let mut line = Vec::<u8>::with_capacity(DEFAULT_BUFFER_CAPACITY);
+            self.config.format.format(&mut line, record)?;
+            line.push(b'\n');
+            return match background_writer.write(line) {
+                Ok(()) => Ok(()),
+                // the background thread has ended
+                Err(line) => self.write_directly(&line),
+            };
         }
 
-        (self.config.format)(state, record)?;
-        state.write_all(b"\n")
+        // format outside the lock, into a buffer that is reused by the calling thread
+        BUFFER.with(|buffer| match buffer.try_borrow_mut() {
+            Ok(mut buffer) => {
+                let result = self.format_and_write(&mut buffer, record);
+                if buffer.capacity() > MAX_BUFFER_CAPACITY {
+                    *buffer = Vec::with_capacity(DEFAULT_BUFFER_CAPACITY);
+                }
+                result
+            }
+            // the format function logs itself, so the buffer is in use already
+            Err(_) => {
+                self.format_and_write(&mut Vec::with_capacity(DEFAULT_BUFFER_CAPACITY), record)
+            }
+        })
     }
 
     #[inline]
     fn flush(&self) -> io::Result<()> {
+        self.error_sink.connect_offered();
+        if let Some(ref background_writer) = self.o_background_writer {
+            if background_writer.flush() {
+                return Ok(());
+            }
+        }
+        let guard = lock_state(&self.state)?;
+        let mut state = guard.borrow_mut();
+        if state.o_failure.is_some() {
+            state.retry(&self.config, true);
+        }
+        state.flush()
+    }
+
+    fn file_statistics(&self) -> Option<FileStatistics> {
+        Some(self.statistics())
+    }
+
+}
+
+// Writes the log lines that a FileLogWriter in async mode has formatted.
+struct BackgroundSink {
+    config: Arc<FileLogWriterConfig>,
+    state: Arc<Mutex<RefCell<FileLogWriterState>>>,
+    error_sink: ErrorSink,
+}
+impl LineSink for BackgroundSink {
+    fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        write_to_state(&self.state, &self.config, &self.error_sink, line)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
         let guard = self.state.lock().unwrap();
         let mut state = guard.borrow_mut();
-        state.lw.flush()
+        if state.o_failure.is_some() {
+            state.retry(&self.config, true);
+        }
+        state.flush()
     }
 }
 
 mod platform {
+    use std::fs::Metadata;
     use std::path::Path;
 
+    // The device and the inode of a file, where available.
+    #[cfg(unix)]
+    pub fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+        use std::os::unix::fs::MetadataExt;
+        Some((metadata.dev(), metadata.ino()))
+    }
+
+    #[cfg(not(unix))]
+    pub fn file_id(_: &Metadata) -> Option<(u64, u64)> {
+        None
+    }
+
     pub fn create_symlink_if_possible(link: &str, path: &Path) {
         linux_create_symlink(link, path);
     }
@@ -1,4 +1,5 @@
 use log::Record;
+use statistics::FileStatistics;
 use std::io;
 
 /// Writes to a single log output stream.
@@ -10,4 +11,10 @@ pub trait LogWriter: Sync + Send {
 
     /// Flushes any buffered records.
     fn flush(&self) -> io::Result<()>;
+
+    /// Provides the counters of the written log files, if the writer writes to files,
+    /// for the [`Statistics`](../struct.Statistics.html) of the logger.
+    fn file_statistics(&self) -> Option<FileStatistics> {
+        None
+    }
 }
@@ -1,6 +1,8 @@
 //! This module contains a trait for additional log writers,
-//! and a configurable concrete implementation
-//! for a log writer that writes to a file or a series of files.
+//! and configurable concrete implementations
+//! for a log writer that writes to a file or a series of files,
+//! for a log writer that keeps the most recent log lines in memory,
+//! and (on unix systems) for a log writer that writes to the syslog.
 //!
 //! Additional log writers can be used to send log messages to other log
 //! ouput streams than the default log file, as for example an alert file or the syslog.
@@ -15,6 +17,11 @@
 //! but to the loggers specified explicitly in the list.
 //! In such a list you can again specify the default logger with the target name `_Default`.
 //!
+//! Log calls that are directed to additional log writers are not filtered by the
+//! log specification of the logger. If you want to filter them, too, register the writer with
+//! [`Logger.add_writer_with_spec()`](../struct.Logger.html#method.add_writer_with_spec)
+//! and a separate `LogSpecification` for this writer.
+//!
 //! In the following example we define an alert writer, and a macro to facilitate using it, and
 //! show some example calls.
 //!
@@ -72,8 +79,16 @@
 //! ```
 //!
 
+mod background_writer;
 mod file_log_writer;
 mod log_writer;
+mod ring_buffer_writer;
+#[cfg(unix)]
+mod syslog_writer;
 
+pub use self::background_writer::OverflowPolicy;
 pub use self::log_writer::LogWriter;
-pub use self::file_log_writer::{FileLogWriter, FileLogWriterBuilder};
+pub use self::ring_buffer_writer::{RingBufferWriter, RingBufferWriterBuilder};
+pub use self::file_log_writer::{Age, FileLogWriter, FileLogWriterBuilder};
+#[cfg(unix)]
+pub use self::syslog_writer::{SyslogFacility, SyslogProtocol, SyslogWriter, SyslogWriterBuilder};
@@ -0,0 +1,298 @@
+use error_handler::ErrorTarget;
+use formats::default_format;
+use formatter::Formatter;
+use log::{Level, LevelFilter, Record};
+use primary_writer::PrimaryWriter;
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use writers::log_writer::LogWriter;
+use FormatFunction;
+
+/// Builder for `RingBufferWriter`.
+pub struct RingBufferWriterBuilder {
+    max_lines: Option<usize>,
+    max_bytes: Option<usize>,
+    level_filter: LevelFilter,
+    format: FormatFunction,
+    dump_file: Option<PathBuf>,
+    dump_on_error: bool,
+}
+
+impl RingBufferWriterBuilder {
+    /// Limits the number of log lines that are kept; the default is 1000.
+    pub fn max_lines(mut self, max_lines: usize) -> RingBufferWriterBuilder {
+        self.max_lines = Some(max_lines);
+        self
+    }
+
+    /// Limits the number of bytes that are kept, in addition to or instead of
+    /// the number of lines; by default, the number of bytes is not limited.
+    pub fn max_bytes(mut self, max_bytes: usize) -> RingBufferWriterBuilder {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Makes the `RingBufferWriter` only keep log lines up to the given level;
+    /// the default is `LevelFilter::Trace`, i.e., all log lines are kept.
+    pub fn level_filter(mut self, level_filter: LevelFilter) -> RingBufferWriterBuilder {
+        self.level_filter = level_filter;
+        self
+    }
+
+    /// Makes the `RingBufferWriter` use the provided format function for the log lines,
+    /// rather than the default ([formats::default_format](../fn.default_format.html)).
+    pub fn format(mut self, format: FormatFunction) -> RingBufferWriterBuilder {
+        self.format = format;
+        self
+    }
+
+    /// Makes [`RingBufferWriter::dump()`](struct.RingBufferWriter.html#method.dump)
+    /// append the kept log lines to the given file, rather than writing them to the
+    /// primary log output of the logger.
+    pub fn dump_file<P: AsRef<Path>>(mut self, dump_file: P) -> RingBufferWriterBuilder {
+        self.dump_file = Some(dump_file.as_ref().to_owned());
+        self
+    }
+
+    /// Makes the `RingBufferWriter` dump the kept log lines whenever an error is logged.
+    ///
+    /// If the dump goes to the primary log output of the logger, the error itself is not part
+    /// of the dump, since the logger writes it there anyway.
+    pub fn dump_on_error(mut self) -> RingBufferWriterBuilder {
+        self.dump_on_error = true;
+        self
+    }
+
+    /// Produces the `RingBufferWriter`.
+    pub fn instantiate(self) -> RingBufferWriter {
+        RingBufferWriter {
+            config: Arc::new(RingBufferConfig {
+                max_lines: self.max_lines.or_else(|| {
+                    if self.max_bytes.is_some() {
+                        None
+                    } else {
+                        Some(1000)
+                    }
+                }),
+                max_bytes: self.max_bytes,
+                level_filter: self.level_filter,
+                formatter: Formatter::Function(self.format),
+                dump_file: self.dump_file,
+                dump_on_error: self.dump_on_error,
+            }),
+            state: Arc::new(Mutex::new(RingBufferState {
+                lines: VecDeque::new(),
+                no_of_bytes: 0,
+                o_primary_writer: None,
+                o_error_target: None,
+            })),
+        }
+    }
+}
+
+struct RingBufferConfig {
+    max_lines: Option<usize>,
+    max_bytes: Option<usize>,
+    level_filter: LevelFilter,
+    formatter: Formatter,
+    dump_file: Option<PathBuf>,
+    dump_on_error: bool,
+}
+
+impl RingBufferConfig {
+    // Checks whether the buffer holds more lines or bytes than allowed.
+    fn exceeded_by(&self, state: &RingBufferState) -> bool {
+        if let Some(max_lines) = self.max_lines {
+            if state.lines.len() > max_lines {
+                return true;
+            }
+        }
+        if let Some(max_bytes) = self.max_bytes {
+            if state.no_of_bytes > max_bytes {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+struct RingBufferState {
+    lines: VecDeque<Vec<u8>>,
+    no_of_bytes: usize,
+    o_primary_writer: Option<Arc<PrimaryWriter>>,
+    o_error_target: Option<ErrorTarget>,
+}
+
+/// A `LogWriter` that keeps the most recent log lines in memory, and writes them out
+/// only on request, or when an error is logged.
+///
+/// This allows having verbose context around failures, without writing verbose logs all
+/// the time.
+///
+/// When registered with [`Logger::ring_buffer()`](../struct.Logger.html#method.ring_buffer),
+/// the `RingBufferWriter` gets all log calls, independent of the log specification of the logger.
+/// It can also be registered like other additional writers with `Logger::add_writer()`.
+///
+/// `RingBufferWriter` is cheap to clone; all clones share the same buffer, so you can keep a clone
+/// for calling [`dump()`](#method.dump).
+///
+/// # Example
+///
+/// ```rust
+/// use flexi_logger::Logger;
+/// use flexi_logger::writers::RingBufferWriter;
+///
+/// let ring_buffer = RingBufferWriter::builder()
+///     .max_lines(500)
+///     .dump_on_error()
+///     .instantiate();
+///
+/// Logger::with_str("info")
+///     .ring_buffer(ring_buffer.clone())
+///     .start()
+///     .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+///
+/// // ...
+///
+/// // write the recent log lines, including the debug and trace lines, to stderr
+/// ring_buffer.dump();
+/// ```
+#[derive(Clone)]
+pub struct RingBufferWriter {
+    config: Arc<RingBufferConfig>,
+    state: Arc<Mutex<RingBufferState>>,
+}
+
+impl RingBufferWriter {
+    /// Instantiates a builder for `RingBufferWriter`.
+    pub fn builder() -> RingBufferWriterBuilder {
+        RingBufferWriterBuilder {
+            max_lines: None,
+            max_bytes: None,
+            level_filter: LevelFilter::Trace,
+            format: default_format,
+            dump_file: None,
+            dump_on_error: false,
+        }
+    }
+
+    /// Writes the kept log lines to the dump file, if one is configured, or otherwise
+    /// to the primary log output of the logger (or to stderr, if the `RingBufferWriter`
+    /// was not registered with `Logger::ring_buffer()`), and empties the buffer.
+    ///
+    /// Failures are handled according to the
+    /// [`ErrorPolicy`](../enum.ErrorPolicy.html) of the logger,
+    /// or reported on stderr if the `RingBufferWriter` was not registered with a logger.
+    pub fn dump(&self) {
+        let (writer, result) = match self.config.dump_file {
+            Some(ref dump_file) => ("ring_buffer", self.dump_to_file(dump_file)),
+            None => {
+                let (lines, o_primary_writer) = self.take();
+                match o_primary_writer {
+                    Some(primary_writer) => (
+                        "primary_writer",
+                        primary_writer
+                            .write_lines(&lines)
+                            .and_then(|()| primary_writer.flush()),
+                    ),
+                    None => ("ring_buffer", io::stderr().write_all(&lines)),
+                }
+            }
+        };
+        if let Err(e) = result {
+            let o_error_target = self.state.lock().unwrap().o_error_target.clone();
+            match o_error_target {
+                Some(target) => target.with_writer(writer).write_failed(e, None),
+                None => eprintln!("FlexiLogger: dumping the ring buffer failed with {}", e),
+            }
+        }
+    }
+
+    /// Appends the kept log lines to the given file, and empties the buffer.
+    pub fn dump_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let (lines, _) = self.take();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(&lines)?;
+        file.flush()
+    }
+
+    pub(crate) fn set_primary_writer(&self, primary_writer: Arc<PrimaryWriter>) {
+        self.state.lock().unwrap().o_primary_writer = Some(primary_writer);
+    }
+
+    pub(crate) fn connect_errors(&self, target: ErrorTarget) {
+        self.state.lock().unwrap().o_error_target = Some(target);
+    }
+
+    pub(crate) fn level_filter(&self) -> LevelFilter {
+        self.config.level_filter
+    }
+
+    fn dumps_to_primary_writer(&self) -> bool {
+        self.config.dump_file.is_none() && self.state.lock().unwrap().o_primary_writer.is_some()
+    }
+
+    // Removes the kept lines from the buffer and returns them as one block,
+    // framed by a header and a trailer.
+    fn take(&self) -> (Vec<u8>, Option<Arc<PrimaryWriter>>) {
+        let (lines, o_primary_writer) = {
+            let mut state = self.state.lock().unwrap();
+            state.no_of_bytes = 0;
+            (mem::take(&mut state.lines), state.o_primary_writer.clone())
+        };
+        let mut buf = Vec::<u8>::with_capacity(lines.iter().map(|l| l.len()).sum::<usize>() + 100);
+        // writing to a Vec cannot fail
+        writeln!(
+            buf,
+            "FlexiLogger: dump of {} buffered log lines:",
+            lines.len()
+        )
+        .ok();
+        for line in lines {
+            buf.extend_from_slice(&line);
+        }
+        writeln!(buf, "FlexiLogger: end of dump").ok();
+        (buf, o_primary_writer)
+    }
+}
+
+impl LogWriter for RingBufferWriter {
+    fn write(&self, record: &Record) -> io::Result<()> {
+        if record.level() > self.config.level_filter {
+            return Ok(());
+        }
+        let dump = self.config.dump_on_error && record.level() == Level::Error;
+        if dump && self.dumps_to_primary_writer() {
+            // the logger writes the error itself to the primary writer, after the dump
+            self.dump();
+            return Ok(());
+        }
+        let mut line = Vec::<u8>::with_capacity(200);
+        self.config.formatter.format(&mut line, record)?;
+        line.push(b'\n');
+        {
+            let config = &self.config;
+            let mut state = self.state.lock().unwrap();
+            state.no_of_bytes += line.len();
+            state.lines.push_back(line);
+            while config.exceeded_by(&state) {
+                match state.lines.pop_front() {
+                    Some(oldest) => state.no_of_bytes -= oldest.len(),
+                    None => break,
+                }
+            }
+        }
+        if dump {
+            self.dump();
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
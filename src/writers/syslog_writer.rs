@@ -0,0 +1,240 @@
+use chrono::Local;
+use formats::default_format;
+use log::{Level, Record};
+use std::env;
+use std::io::{self, Write};
+use std::os::unix::net::UnixDatagram;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Mutex;
+use writers::log_writer::LogWriter;
+use FlexiLoggerError;
+use FormatFunction;
+
+/// The syslog facility, which is sent with each log line to the syslog.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SyslogFacility {
+    /// Kernel messages.
+    Kernel = 0,
+    /// User-level messages.
+    User = 1,
+    /// Mail system.
+    Mail = 2,
+    /// System daemons.
+    Daemon = 3,
+    /// Security/authorization messages.
+    Auth = 4,
+    /// Messages generated internally by syslogd.
+    Syslog = 5,
+    /// Line printer subsystem.
+    Lpr = 6,
+    /// Network news subsystem.
+    News = 7,
+    /// UUCP subsystem.
+    Uucp = 8,
+    /// Clock daemon.
+    Cron = 9,
+    /// Security/authorization messages (private).
+    AuthPriv = 10,
+    /// FTP daemon.
+    Ftp = 11,
+    /// Locally used facility 0.
+    Local0 = 16,
+    /// Locally used facility 1.
+    Local1 = 17,
+    /// Locally used facility 2.
+    Local2 = 18,
+    /// Locally used facility 3.
+    Local3 = 19,
+    /// Locally used facility 4.
+    Local4 = 20,
+    /// Locally used facility 5.
+    Local5 = 21,
+    /// Locally used facility 6.
+    Local6 = 22,
+    /// Locally used facility 7.
+    Local7 = 23,
+}
+
+/// The protocol that is used for sending log lines to the syslog.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SyslogProtocol {
+    /// The BSD syslog protocol (RFC 3164), which is understood by all syslog implementations.
+    Rfc3164,
+    /// The syslog protocol (RFC 5424).
+    Rfc5424,
+}
+
+// The syslog severity for a log level.
+fn severity(level: Level) -> u8 {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    }
+}
+
+/// Builder for `SyslogWriter`.
+pub struct SyslogWriterBuilder {
+    path: PathBuf,
+    facility: SyslogFacility,
+    protocol: SyslogProtocol,
+    app_name: Option<String>,
+    format: FormatFunction,
+}
+
+impl SyslogWriterBuilder {
+    /// Specifies the path of the unix datagram socket of the syslog; the default is `/dev/log`.
+    pub fn path<P: AsRef<Path>>(mut self, path: P) -> SyslogWriterBuilder {
+        self.path = path.as_ref().to_owned();
+        self
+    }
+
+    /// Specifies the facility; the default is `SyslogFacility::User`.
+    pub fn facility(mut self, facility: SyslogFacility) -> SyslogWriterBuilder {
+        self.facility = facility;
+        self
+    }
+
+    /// Specifies the protocol; the default is `SyslogProtocol::Rfc3164`.
+    pub fn protocol(mut self, protocol: SyslogProtocol) -> SyslogWriterBuilder {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Specifies the application name that is sent with each log line;
+    /// the default is the name of the program.
+    pub fn app_name<S: Into<String>>(mut self, app_name: S) -> SyslogWriterBuilder {
+        self.app_name = Some(app_name.into());
+        self
+    }
+
+    /// Makes the `SyslogWriter` use the provided format function for the messages,
+    /// rather than the default ([formats::default_format](../fn.default_format.html)).
+    ///
+    /// The timestamp, the severity, and the application name are added according to the
+    /// syslog protocol, so the format function does not need to write them.
+    pub fn format(mut self, format: FormatFunction) -> SyslogWriterBuilder {
+        self.format = format;
+        self
+    }
+
+    /// Produces the `SyslogWriter`, which is connected to the syslog socket.
+    pub fn instantiate(self) -> Result<SyslogWriter, FlexiLoggerError> {
+        let app_name = self.app_name.unwrap_or_else(|| {
+            let arg0 = env::args().nth(0).unwrap_or_else(|| "rs".to_owned());
+            Path::new(&arg0)
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "rs".to_owned())
+        });
+        let socket = connect(&self.path)?;
+        Ok(SyslogWriter {
+            path: self.path,
+            facility: self.facility,
+            protocol: self.protocol,
+            app_name,
+            pid: process::id(),
+            format: self.format,
+            socket: Mutex::new(socket),
+        })
+    }
+}
+
+fn connect(path: &Path) -> io::Result<UnixDatagram> {
+    let socket = UnixDatagram::unbound()?;
+    socket.connect(path)?;
+    Ok(socket)
+}
+
+/// A `LogWriter` that sends the log lines to the local syslog,
+/// via a unix datagram socket.
+///
+/// Each log level is mapped to a syslog severity: `Error` to error, `Warn` to warning,
+/// `Info` to informational, `Debug` and `Trace` to debug.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use flexi_logger::Logger;
+/// use flexi_logger::writers::{SyslogFacility, SyslogWriter};
+///
+/// Logger::with_str("info")
+///     .add_writer(
+///         "Syslog",
+///         Box::new(
+///             SyslogWriter::builder()
+///                 .facility(SyslogFacility::Local0)
+///                 .app_name("myprog")
+///                 .instantiate()
+///                 .unwrap(),
+///         ),
+///     )
+///     .start()
+///     .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+/// ```
+pub struct SyslogWriter {
+    path: PathBuf,
+    facility: SyslogFacility,
+    protocol: SyslogProtocol,
+    app_name: String,
+    pid: u32,
+    format: FormatFunction,
+    socket: Mutex<UnixDatagram>,
+}
+
+impl SyslogWriter {
+    /// Instantiates a builder for `SyslogWriter`.
+    pub fn builder() -> SyslogWriterBuilder {
+        SyslogWriterBuilder {
+            path: PathBuf::from("/dev/log"),
+            facility: SyslogFacility::User,
+            protocol: SyslogProtocol::Rfc3164,
+            app_name: None,
+            format: default_format,
+        }
+    }
+
+    fn format_message(&self, record: &Record) -> io::Result<Vec<u8>> {
+        let mut buf = Vec::<u8>::with_capacity(200);
+        let pri = self.facility as u8 * 8 + severity(record.level());
+        match self.protocol {
+            SyslogProtocol::Rfc3164 => write!(
+                buf,
+                "<{}>{} {}[{}]: ",
+                pri,
+                Local::now().format("%b %e %H:%M:%S"),
+                self.app_name,
+                self.pid
+            )?,
+            SyslogProtocol::Rfc5424 => write!(
+                buf,
+                "<{}>1 {} - {} {} - - ",
+                pri,
+                Local::now().format("%Y-%m-%dT%H:%M:%S%.6f%:z"),
+                self.app_name,
+                self.pid
+            )?,
+        }
+        (self.format)(&mut buf, record)?;
+        Ok(buf)
+    }
+}
+
+impl LogWriter for SyslogWriter {
+    fn write(&self, record: &Record) -> io::Result<()> {
+        let message = self.format_message(record)?;
+        let mut socket = self.socket.lock().unwrap();
+        if socket.send(&message).is_err() {
+            // the syslog might have been restarted, so we try once to reconnect
+            *socket = connect(&self.path)?;
+            socket.send(&message)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+extern crate chrono;
+extern crate flexi_logger;
+extern crate glob;
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::writers::{FileLogWriter, LogWriter};
+use flexi_logger::OverflowPolicy;
+use glob::glob;
+use log::{Level, Record};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::sync::Arc;
+use std::thread;
+
+const NO_OF_THREADS: usize = 4;
+const NO_OF_LOGLINES_PER_THREAD: usize = 5_000;
+
+#[test]
+fn test_async_mode_block() {
+    let (lines, dropped) = run_with(OverflowPolicy::Block);
+    assert_eq!(lines, NO_OF_THREADS * NO_OF_LOGLINES_PER_THREAD);
+    assert_eq!(dropped, 0);
+}
+
+#[test]
+fn test_async_mode_drop_newest() {
+    let (lines, dropped) = run_with(OverflowPolicy::DropNewest);
+    assert_eq!(lines + dropped, NO_OF_THREADS * NO_OF_LOGLINES_PER_THREAD);
+}
+
+#[test]
+fn test_async_mode_drop_oldest() {
+    let (lines, dropped) = run_with(OverflowPolicy::DropOldest);
+    assert_eq!(lines + dropped, NO_OF_THREADS * NO_OF_LOGLINES_PER_THREAD);
+}
+
+// Writes from several threads with a small queue, flushes, and returns the number of
+// log lines in the file and the number of dropped log lines that were reported.
+fn run_with(overflow: OverflowPolicy) -> (usize, usize) {
+    let directory = format!(
+        "./log_files/async/{:?}/{}",
+        overflow,
+        Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+    );
+    let writer = Arc::new(
+        FileLogWriter::builder()
+            .directory(directory.clone())
+            .async_mode(10, overflow)
+            .instantiate()
+            .unwrap_or_else(|e| panic!("FileLogWriter initialization failed with {}", e)),
+    );
+
+    let worker_handles: Vec<_> = (0..NO_OF_THREADS)
+        .map(|thread_number| {
+            let writer = Arc::clone(&writer);
+            thread::spawn(move || {
+                for idx in 0..NO_OF_LOGLINES_PER_THREAD {
+                    writer
+                        .write(
+                            &Record::builder()
+                                .args(format_args!("({}) writing out line {}", thread_number, idx))
+                                .level(Level::Info)
+                                .module_path(Some("test_async_mode"))
+                                .build(),
+                        )
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    for worker_handle in worker_handles {
+        worker_handle.join().unwrap();
+    }
+    writer.flush().unwrap();
+
+    count_lines(&directory)
+}
+
+fn count_lines(directory: &str) -> (usize, usize) {
+    let mut lines = 0;
+    let mut dropped = 0;
+    for globresult in glob(&format!("{}/*.log", directory)).unwrap() {
+        let reader = BufReader::new(File::open(globresult.unwrap()).unwrap());
+        for line in reader.lines() {
+            let line = line.unwrap();
+            if line.starts_with("INFO [test_async_mode] (") {
+                lines += 1;
+            } else if line.starts_with("FlexiLogger: ") {
+                dropped += line
+                    .split(' ')
+                    .nth(1)
+                    .unwrap()
+                    .parse::<usize>()
+                    .unwrap();
+            } else {
+                panic!("irregular line in log file: \"{}\"", line);
+            }
+        }
+    }
+    (lines, dropped)
+}
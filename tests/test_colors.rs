@@ -0,0 +1,64 @@
+extern crate chrono;
+extern crate flexi_logger;
+extern crate glob;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::{Duplicate, Logger, Palette};
+use glob::glob;
+use std::fs::File;
+use std::io::Read;
+
+#[test]
+fn test_colors() {
+    parse_palette();
+
+    let directory = format!(
+        "./log_files/colors/{}",
+        Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+    );
+    Logger::with_str("info")
+        .log_to_file()
+        .directory(directory.clone())
+        .duplicate_to_stderr(Duplicate::All)
+        .palette(Palette::parse("1;2;3;-;5").unwrap())
+        .start()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    error!("This is an error message");
+    warn!("This is a warning");
+    info!("This is an info message");
+    log::logger().flush();
+
+    // the file output is never colored
+    let path = glob(&format!("{}/*.log", directory))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let mut content = String::new();
+    File::open(path)
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!(content.lines().count(), 3);
+    assert!(!content.contains('\x1b'));
+}
+
+fn parse_palette() {
+    assert_eq!(Palette::parse("196;208;-;7;8").unwrap(), Palette::default());
+    assert_eq!(
+        Palette::parse(" 1; 2 ;3;4;- ").unwrap(),
+        Palette {
+            error: Some(1),
+            warn: Some(2),
+            info: Some(3),
+            debug: Some(4),
+            trace: None,
+        }
+    );
+    assert!(Palette::parse("1;2;3;4").is_err());
+    assert!(Palette::parse("1;2;3;4;256").is_err());
+    assert!(Palette::parse("1;2;red;4;5").is_err());
+}
@@ -0,0 +1,126 @@
+#[cfg(feature = "compress")]
+extern crate chrono;
+#[cfg(feature = "compress")]
+extern crate flate2;
+#[cfg(feature = "compress")]
+extern crate flexi_logger;
+#[cfg(feature = "compress")]
+extern crate glob;
+#[cfg(feature = "compress")]
+extern crate log;
+
+#[cfg(feature = "compress")]
+use chrono::Local;
+#[cfg(feature = "compress")]
+use flate2::read::GzDecoder;
+#[cfg(feature = "compress")]
+use flexi_logger::writers::{FileLogWriter, LogWriter};
+#[cfg(feature = "compress")]
+use glob::glob;
+#[cfg(feature = "compress")]
+use log::{Level, Record};
+#[cfg(feature = "compress")]
+use std::fs::File;
+#[cfg(feature = "compress")]
+use std::io::Read;
+#[cfg(feature = "compress")]
+use std::{thread, time};
+
+#[cfg(feature = "compress")]
+#[cfg_attr(feature = "compress", test)]
+fn test_compress() {
+    let directory = format!(
+        "./log_files/compress/{}",
+        Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+    );
+    let writer = instantiate(&directory);
+    write_lines(&writer, 0, 100);
+    writer.flush().unwrap();
+
+    // the compression runs in the background
+    thread::sleep(time::Duration::from_millis(500));
+
+    let compressed = list_files(&directory, "log.gz");
+    let plain = list_files(&directory, "log");
+    assert_eq!(compressed.len(), 2, "unexpected files: {:?}", compressed);
+    assert_eq!(plain.len(), 1, "unexpected files: {:?}", plain);
+    assert!(plain[0].ends_with("_r00003.log"));
+
+    let mut content = String::new();
+    GzDecoder::new(File::open(&compressed[0]).unwrap())
+        .read_to_string(&mut content)
+        .unwrap();
+    assert!(content.starts_with("INFO [test_compress] this is log line number 0\n"));
+
+    // a new writer continues the numbering after the compressed files
+    drop(writer);
+    let writer = instantiate(&directory);
+    write_lines(&writer, 100, 1);
+    writer.flush().unwrap();
+    assert!(list_files(&directory, "log")[1].ends_with("_r00004.log"));
+}
+
+#[cfg(feature = "compress")]
+#[cfg_attr(feature = "compress", test)]
+fn test_compress_with_max_backup() {
+    let directory = format!(
+        "./log_files/compress_max_backup/{}",
+        Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+    );
+    let writer = FileLogWriter::builder()
+        .directory(directory.clone())
+        .rotate_over_size(2000)
+        .max_backup(3)
+        .compress()
+        .instantiate()
+        .unwrap_or_else(|e| panic!("FileLogWriter initialization failed with {}", e));
+    for _ in 0..10 {
+        write_lines(&writer, 0, 50);
+        thread::sleep(time::Duration::from_millis(50));
+    }
+    writer.flush().unwrap();
+
+    // the compression runs in the background, and removes the old files when it is done
+    thread::sleep(time::Duration::from_millis(500));
+
+    let compressed = list_files(&directory, "log.gz");
+    let plain = list_files(&directory, "log");
+    assert_eq!(plain.len(), 1, "unexpected files: {:?}", plain);
+    assert_eq!(compressed.len(), 2, "unexpected files: {:?}", compressed);
+    assert!(compressed[1] < plain[0]);
+}
+
+#[cfg(feature = "compress")]
+fn instantiate(directory: &str) -> FileLogWriter {
+    FileLogWriter::builder()
+        .directory(directory)
+        .rotate_over_size(2000)
+        .compress()
+        .instantiate()
+        .unwrap_or_else(|e| panic!("FileLogWriter initialization failed with {}", e))
+}
+
+#[cfg(feature = "compress")]
+fn write_lines(writer: &FileLogWriter, start: usize, count: usize) {
+    for idx in start..start + count {
+        writer
+            .write(
+                &Record::builder()
+                    .args(format_args!("this is log line number {}", idx))
+                    .level(Level::Info)
+                    .module_path(Some("test_compress"))
+                    .build(),
+            )
+            .unwrap();
+    }
+}
+
+#[cfg(feature = "compress")]
+fn list_files(directory: &str, suffix: &str) -> Vec<String> {
+    let mut files: Vec<String> = glob(&format!("{}/*.{}", directory, suffix))
+        .unwrap()
+        .map(|globresult| globresult.unwrap().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    files
+}
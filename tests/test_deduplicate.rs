@@ -0,0 +1,58 @@
+extern crate chrono;
+extern crate flexi_logger;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::{detailed_format, Logger};
+use std::fs;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn test_deduplicate() {
+    let directory = format!(
+        "log_files/deduplicate/{}",
+        Local::now().format("%Y-%m-%d_%H-%M-%S")
+    );
+    Logger::with_str("info")
+        .format(detailed_format)
+        .log_to_file()
+        .directory(directory.clone())
+        .suppress_timestamp()
+        .deduplicate(Duration::from_millis(1000))
+        .start()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    for _ in 0..100 {
+        warn!("The dependency is flapping");
+    }
+    info!("Doing something else");
+    info!("Doing something else");
+    info!("Doing something else");
+    thread::sleep(Duration::from_millis(1100));
+    info!("Doing something else");
+    error!("Something different");
+    warn!("Flushed");
+    warn!("Flushed");
+    warn!("Flushed");
+    log::logger().flush();
+
+    let path = fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension() == Some("log".as_ref()))
+        .unwrap();
+    let content = fs::read_to_string(path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 8, "unexpected content:\n{}", content);
+    assert!(lines[0].ends_with("The dependency is flapping"));
+    assert!(lines[1].contains("WARN"));
+    assert!(lines[1].ends_with("last message repeated 99 times"));
+    assert!(lines[2].ends_with("Doing something else"));
+    assert!(lines[3].ends_with("last message repeated 2 times"));
+    assert!(lines[4].ends_with("Doing something else"));
+    assert!(lines[5].ends_with("Something different"));
+    assert!(lines[6].ends_with("Flushed"));
+    assert!(lines[7].ends_with("last message repeated 2 times"));
+}
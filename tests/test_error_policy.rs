@@ -0,0 +1,54 @@
+extern crate flexi_logger;
+#[macro_use]
+extern crate log;
+
+use flexi_logger::writers::LogWriter;
+use flexi_logger::{ErrorPolicy, Logger, ModuleLimit, Record};
+use std::io;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_error_policy() {
+    let errors = Arc::new(Mutex::new(Vec::<String>::new()));
+    let errors_clone = Arc::clone(&errors);
+    let handle = Logger::with_str("info")
+        .add_writer("broken", Box::new(BrokenWriter))
+        .error_policy(ErrorPolicy::Callback(Box::new(move |e| {
+            errors_clone.lock().unwrap().push(e.to_string());
+        })))
+        .limit_error_reports(ModuleLimit::rate(1, 3))
+        .start_reconfigurable()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    error!(target: "{nowhere}", "This goes nowhere");
+    for i in 0..10 {
+        error!(target: "{broken}", "This is broken {}", i);
+    }
+    log::logger().flush();
+
+    assert_eq!(
+        *errors.lock().unwrap(),
+        vec![
+            "bad writer spec: nowhere".to_string(),
+            "writing log line to broken failed with disk is broken".to_string(),
+            "writing log line to broken failed with disk is broken".to_string(),
+            "8 further errors were not reported".to_string(),
+        ]
+    );
+
+    let statistics = handle.statistics();
+    assert_eq!(statistics.bad_writer_names, 1);
+    assert_eq!(statistics.writers["broken"].records, 0);
+    assert_eq!(statistics.writers["broken"].write_errors, 10);
+}
+
+struct BrokenWriter;
+impl LogWriter for BrokenWriter {
+    fn write(&self, _record: &Record) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Other, "disk is broken"))
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
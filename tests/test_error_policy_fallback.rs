@@ -0,0 +1,67 @@
+extern crate chrono;
+extern crate flexi_logger;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::writers::{FileLogWriter, LogWriter, RingBufferWriter};
+use flexi_logger::{ErrorPolicy, Logger, Record};
+use std::fs;
+use std::io;
+
+#[test]
+fn test_error_policy_fallback() {
+    let directory = format!(
+        "log_files/error_policy_fallback/{}",
+        Local::now().format("%Y-%m-%d_%H-%M-%S")
+    );
+    let fallback = FileLogWriter::builder()
+        .directory(directory.clone())
+        .discriminant("fallback")
+        .suppress_timestamp()
+        .instantiate()
+        .unwrap();
+    // the directory of the dump file does not exist, so dumping fails
+    let ring_buffer = RingBufferWriter::builder()
+        .dump_file(format!("{}/missing/dump.log", directory))
+        .dump_on_error()
+        .instantiate();
+    let handle = Logger::with_str("info")
+        .log_to_file()
+        .directory(directory.clone())
+        .suppress_timestamp()
+        .add_writer("broken", Box::new(BrokenWriter))
+        .ring_buffer(ring_buffer)
+        .error_policy(ErrorPolicy::Fallback(Box::new(fallback)))
+        .start_reconfigurable()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    warn!(target: "{broken}", "This is broken");
+    error!("This triggers a dump");
+    log::logger().flush();
+
+    let path = fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.to_string_lossy().ends_with("_fallback.log"))
+        .unwrap();
+    let content = fs::read_to_string(path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 3, "unexpected content:\n{}", content);
+    assert!(lines[0].ends_with("This is broken"));
+    assert!(lines[1].ends_with("writing log line to broken failed with disk is broken"));
+    assert!(lines[2].contains("writing log line to ring_buffer failed with "));
+
+    assert_eq!(handle.statistics().writers["broken"].write_errors, 1);
+}
+
+struct BrokenWriter;
+impl LogWriter for BrokenWriter {
+    fn write(&self, _record: &Record) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Other, "disk is broken"))
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
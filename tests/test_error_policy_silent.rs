@@ -0,0 +1,67 @@
+extern crate chrono;
+extern crate flexi_logger;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::writers::{LogWriter, RingBufferWriter};
+use flexi_logger::{ErrorPolicy, Logger, Record};
+use std::fs;
+use std::io;
+
+#[test]
+fn test_error_policy_silent() {
+    let directory = format!(
+        "log_files/error_policy_silent/{}",
+        Local::now().format("%Y-%m-%d_%H-%M-%S")
+    );
+    // the directory of the dump file does not exist, so dumping fails
+    let ring_buffer = RingBufferWriter::builder()
+        .dump_file(format!("{}/missing/dump.log", directory))
+        .dump_on_error()
+        .instantiate();
+    let handle = Logger::with_str("info")
+        .log_to_file()
+        .directory(directory.clone())
+        .suppress_timestamp()
+        .add_writer("broken", Box::new(BrokenWriter))
+        .ring_buffer(ring_buffer)
+        .error_policy(ErrorPolicy::Silent)
+        .start_reconfigurable()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    for i in 0..3 {
+        warn!(target: "{broken,_Default}", "This is broken {}", i);
+    }
+    error!(target: "{nowhere}", "This goes nowhere");
+    error!("This triggers a dump");
+    log::logger().flush();
+
+    let statistics = handle.statistics();
+    assert_eq!(statistics.writers["broken"].write_errors, 3);
+    assert_eq!(statistics.bad_writer_names, 1);
+    assert_eq!(statistics.write_errors, 0);
+
+    // logging goes on, and nothing but the log lines is written
+    let path = fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension() == Some("log".as_ref()))
+        .unwrap();
+    let content = fs::read_to_string(path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 4, "unexpected content:\n{}", content);
+    assert!(lines[2].ends_with("This is broken 2"));
+    assert!(lines[3].ends_with("This triggers a dump"));
+}
+
+struct BrokenWriter;
+impl LogWriter for BrokenWriter {
+    fn write(&self, _record: &Record) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Other, "disk is broken"))
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+extern crate chrono;
+extern crate flexi_logger;
+extern crate glob;
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::writers::{FileLogWriter, LogWriter};
+use glob::glob;
+use log::{Level, Record};
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn test_reopen_on_external_rotation() {
+    let directory = directory("detected");
+    let writer = FileLogWriter::builder()
+        .directory(directory.clone())
+        .reopen_on_external_rotation()
+        .instantiate()
+        .unwrap_or_else(|e| panic!("FileLogWriter initialization failed with {}", e));
+
+    write(&writer, "line 1");
+    let path = current_file(&directory);
+    let rotated_path = path.with_extension("log.1");
+    fs::rename(&path, &rotated_path).unwrap();
+    write(&writer, "line 2");
+
+    // the check for external rotation is done at most once per second
+    thread::sleep(Duration::from_millis(1100));
+    write(&writer, "line 3");
+
+    assert_eq!(
+        fs::read_to_string(&rotated_path).unwrap(),
+        "INFO [test_external_rotation] line 1\nINFO [test_external_rotation] line 2\n"
+    );
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "INFO [test_external_rotation] line 3\n"
+    );
+}
+
+#[test]
+fn test_reopen_output_file() {
+    let directory = directory("explicit");
+    let writer = FileLogWriter::builder()
+        .directory(directory.clone())
+        .instantiate()
+        .unwrap_or_else(|e| panic!("FileLogWriter initialization failed with {}", e));
+
+    write(&writer, "line 1");
+    let path = current_file(&directory);
+    fs::remove_file(&path).unwrap();
+    write(&writer, "line 2 - you must not see it!");
+    writer.reopen_output_file().unwrap();
+    write(&writer, "line 3");
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "INFO [test_external_rotation] line 3\n"
+    );
+}
+
+fn directory(name: &str) -> String {
+    format!(
+        "./log_files/external_rotation/{}/{}",
+        name,
+        Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+    )
+}
+
+fn current_file(directory: &str) -> PathBuf {
+    glob(&format!("{}/*.log", directory))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+}
+
+fn write(writer: &FileLogWriter, message: &str) {
+    writer
+        .write(
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .level(Level::Info)
+                .module_path(Some("test_external_rotation"))
+                .build(),
+        )
+        .unwrap();
+    writer.flush().unwrap();
+}
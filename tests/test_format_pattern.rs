@@ -0,0 +1,132 @@
+extern crate chrono;
+extern crate flexi_logger;
+extern crate glob;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::writers::{FileLogWriter, LogWriter};
+use flexi_logger::{FlexiLoggerError, Logger};
+use glob::glob;
+use log::{Level, Record};
+use std::fs::File;
+use std::io::Read;
+
+#[test]
+fn test_format_pattern() {
+    parse_errors();
+    file_log_writer_with_pattern();
+    logger_with_pattern();
+    format_function();
+}
+
+fn format_function() {
+    let writer = FileLogWriter::builder()
+        .directory(define_directory("function"))
+        .format(flexi_logger::detailed_format)
+        .instantiate()
+        .unwrap_or_else(|e| panic!("FileLogWriter initialization failed with {}", e));
+    let record = Record::builder()
+        .args(format_args!("This is an info"))
+        .level(Level::Info)
+        .module_path(Some("test_format_pattern"))
+        .build();
+    let mut line = Vec::<u8>::new();
+    (writer.format().unwrap())(&mut line, &record).unwrap();
+    let line = String::from_utf8(line).unwrap();
+    assert!(line.ends_with(" INFO [test_format_pattern] <unnamed>:0: This is an info"));
+}
+
+fn parse_errors() {
+    for pattern in &["{msg", "{msg}}", "{message}", "{level:x}", "{ts:%Q}"] {
+        match FileLogWriter::builder()
+            .directory(define_directory("errors"))
+            .format_pattern(*pattern)
+            .instantiate()
+        {
+            Err(FlexiLoggerError::Parse(_)) => {}
+            Err(e) => panic!("unexpected error for pattern {}: {}", pattern, e),
+            Ok(_) => panic!("pattern {} was accepted", pattern),
+        }
+    }
+
+    match Logger::with_str("info")
+        .format_pattern("{lvl} {msg}")
+        .start()
+    {
+        Err(FlexiLoggerError::Parse(_)) => {}
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(_) => panic!("pattern was accepted"),
+    }
+}
+
+fn file_log_writer_with_pattern() {
+    let directory = define_directory("flw");
+    let writer = FileLogWriter::builder()
+        .directory(directory.clone())
+        .format_pattern("{{{level:5}}} {level:>5}|{module}|{target}|{file}:{line}|{msg}")
+        .instantiate()
+        .unwrap_or_else(|e| panic!("FileLogWriter initialization failed with {}", e));
+    assert!(writer.format().is_none());
+    writer
+        .write(
+            &Record::builder()
+                .args(format_args!("This is a warning"))
+                .level(Level::Warn)
+                .target("my_target")
+                .module_path(Some("test_format_pattern"))
+                .file(Some("tests/test_format_pattern.rs"))
+                .line(Some(42))
+                .build(),
+        )
+        .unwrap();
+    writer.flush().unwrap();
+
+    assert_eq!(
+        read_log_file(&directory),
+        "{WARN }  WARN|test_format_pattern|my_target|tests/test_format_pattern.rs:42|\
+         This is a warning\n"
+    );
+}
+
+fn logger_with_pattern() {
+    let directory = define_directory("logger");
+    Logger::with_str("info")
+        .log_to_file()
+        .directory(directory.clone())
+        .format_pattern("{ts:%Y-%m-%d} {level} {thread} {msg}")
+        .start()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+    error!("This is an error message");
+    log::logger().flush();
+
+    assert_eq!(
+        read_log_file(&directory),
+        format!(
+            "{} ERROR test_format_pattern This is an error message\n",
+            Local::now().format("%Y-%m-%d")
+        )
+    );
+}
+
+fn read_log_file(directory: &str) -> String {
+    let path = glob(&format!("{}/*.log", directory))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let mut content = String::new();
+    File::open(path)
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    content
+}
+
+fn define_directory(name: &str) -> String {
+    format!(
+        "./log_files/format_pattern/{}/{}",
+        name,
+        Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+    )
+}
@@ -0,0 +1,84 @@
+extern crate chrono;
+extern crate flexi_logger;
+extern crate glob;
+extern crate log;
+extern crate serde_json;
+
+use chrono::Local;
+use flexi_logger::json_format;
+use flexi_logger::writers::{FileLogWriter, LogWriter};
+use glob::glob;
+use log::{Level, Record};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::thread;
+
+#[test]
+fn test_json_format() {
+    let directory = format!(
+        "./log_files/json/{}",
+        Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+    );
+    let writer = FileLogWriter::builder()
+        .format(json_format)
+        .directory(directory.clone())
+        .instantiate()
+        .unwrap_or_else(|e| panic!("FileLogWriter initialization failed with {}", e));
+
+    let message = "A \"quoted\" text\nwith a \\ backslash, a\ttab, and a \u{1} control character";
+    writer
+        .write(
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .level(Level::Warn)
+                .target("my_target")
+                .module_path(Some("test_json_format::inner"))
+                .file(Some("tests/test_json_format.rs"))
+                .line(Some(42))
+                .build(),
+        )
+        .unwrap();
+    writer
+        .write(
+            &Record::builder()
+                .args(format_args!("without location"))
+                .level(Level::Info)
+                .build(),
+        )
+        .unwrap();
+    writer.flush().unwrap();
+
+    let lines: Vec<Value> = read_lines(&directory)
+        .iter()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+
+    assert!(lines[0]["timestamp"].is_string());
+    assert_eq!(lines[0]["level"], "WARN");
+    assert_eq!(lines[0]["target"], "my_target");
+    assert_eq!(lines[0]["module_path"], "test_json_format::inner");
+    assert_eq!(lines[0]["file"], "tests/test_json_format.rs");
+    assert_eq!(lines[0]["line"], 42);
+    assert_eq!(lines[0]["thread"], thread::current().name().unwrap());
+    assert_eq!(lines[0]["message"], message);
+
+    assert_eq!(lines[1]["level"], "INFO");
+    assert!(lines[1]["module_path"].is_null());
+    assert!(lines[1]["file"].is_null());
+    assert!(lines[1]["line"].is_null());
+    assert_eq!(lines[1]["message"], "without location");
+}
+
+fn read_lines(directory: &str) -> Vec<String> {
+    let path = glob(&format!("{}/*.log", directory))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    BufReader::new(File::open(path).unwrap())
+        .lines()
+        .map(|line| line.unwrap())
+        .collect()
+}
@@ -0,0 +1,122 @@
+extern crate chrono;
+extern crate flexi_logger;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::{LogSpecBuilder, LogSpecification, Logger, ModuleLimit};
+use log::LevelFilter;
+use std::fs;
+
+#[test]
+fn test_limits() {
+    let directory = directory("logger");
+    Logger::with_str("warn, test_limits = info@sample=10, rated = info@rate=1;burst=5")
+        .log_to_file()
+        .directory(directory.clone())
+        .suppress_timestamp()
+        .start()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    for i in 0..100 {
+        info!("sampled {}", i);
+    }
+    for i in 0..20 {
+        info!(target: "rated", "burst line {}", i);
+    }
+    log::logger().flush();
+
+    let path = fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension() == Some("log".as_ref()))
+        .unwrap();
+    let content = fs::read_to_string(path).unwrap();
+    assert_eq!(content.matches("sampled").count(), 10);
+    assert!(content.contains("sampled 0\n"));
+    assert!(content.contains("sampled 90\n"));
+    assert_eq!(content.matches("burst line").count(), 5);
+    assert!(content.contains("90 log lines were dropped due to the limit for test_limits"));
+    assert!(content.contains("15 log lines were dropped due to the limit for rated"));
+}
+
+#[test]
+fn test_parse() {
+    let spec = LogSpecification::parse("info@rate=100, hot = debug@rate=100;burst=500, cold");
+    assert_eq!(
+        spec.module_limit(None),
+        Some(ModuleLimit {
+            rate: Some(100),
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        spec.module_limit(Some("hot")),
+        Some(ModuleLimit::rate(100, 500))
+    );
+    assert_eq!(spec.module_limit(Some("cold")), None);
+
+    // invalid limits are ignored
+    let spec = LogSpecification::parse("info, hot = debug@sample=0, cold = info@foo=1");
+    assert_eq!(spec.module_limit(Some("hot")), None);
+    assert_eq!(spec.module_limit(Some("cold")), None);
+    assert!(spec.enabled(log::Level::Debug, "hot"));
+
+    assert!(ModuleLimit::parse("sample=10;rate=5").is_ok());
+    assert!(ModuleLimit::parse("").is_err());
+    assert!(ModuleLimit::parse("rate=x").is_err());
+    assert!(ModuleLimit::parse("rate=0").is_err());
+    assert_eq!(
+        ModuleLimit::parse("sample=10;rate=5;burst=7")
+            .unwrap()
+            .to_string(),
+        "sample=10;rate=5;burst=7"
+    );
+}
+
+#[test]
+fn test_builder() {
+    let mut builder = LogSpecBuilder::new();
+    builder
+        .default(LevelFilter::Info)
+        .module("hot", LevelFilter::Debug)
+        .limit("hot", ModuleLimit::sample(10))
+        .limit("unknown", ModuleLimit::sample(10));
+    let spec = builder.build();
+    assert_eq!(
+        spec.module_limit(Some("hot")),
+        Some(ModuleLimit::sample(10))
+    );
+    assert_eq!(spec.module_limit(Some("unknown")), None);
+
+    builder.remove("hot");
+    assert_eq!(builder.build().module_limit(Some("hot")), None);
+}
+
+#[cfg(feature = "specfile")]
+#[test]
+fn test_specfile() {
+    let specfile = std::path::PathBuf::from(format!("{}/logspec.toml", directory("specfile")));
+    LogSpecification::parse("info@sample=2, hot = debug@rate=100;burst=500")
+        .ensure_specfile_is_valid(&specfile)
+        .unwrap();
+    let spec = LogSpecification::file(&specfile).unwrap();
+    assert_eq!(spec.module_limit(None), Some(ModuleLimit::sample(2)));
+    assert_eq!(
+        spec.module_limit(Some("hot")),
+        Some(ModuleLimit::rate(100, 500))
+    );
+
+    fs::write(&specfile, "global_level = 'info@sample=x'\n").unwrap();
+    assert!(LogSpecification::file(&specfile).is_err());
+}
+
+fn directory(test: &str) -> String {
+    let directory = format!(
+        "log_files/limits/{}/{}",
+        test,
+        Local::now().format("%Y-%m-%d_%H-%M-%S")
+    );
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
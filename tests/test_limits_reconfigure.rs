@@ -0,0 +1,47 @@
+extern crate chrono;
+extern crate flexi_logger;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::Logger;
+use std::fs;
+
+#[test]
+fn test_limits_reconfigure() {
+    let directory = format!(
+        "log_files/limits_reconfigure/{}",
+        Local::now().format("%Y-%m-%d_%H-%M-%S")
+    );
+    let mut handle = Logger::with_str("info, rated = info@rate=1;burst=3")
+        .log_to_file()
+        .directory(directory.clone())
+        .suppress_timestamp()
+        .start_reconfigurable()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    for i in 0..10 {
+        info!(target: "rated", "rated line {}", i);
+    }
+    // the limit of rated is unchanged, so it keeps its state
+    handle.parse_new_spec("info, rated = info@rate=1;burst=3, other = debug");
+    for i in 10..15 {
+        info!(target: "rated", "rated line {}", i);
+    }
+    // the limit of rated is removed, the dropped log lines are still noted
+    handle.parse_and_push_temp_spec("warn");
+    log::logger().flush();
+
+    let path = fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension() == Some("log".as_ref()))
+        .unwrap();
+    let content = fs::read_to_string(path).unwrap();
+    assert_eq!(content.matches("rated line").count(), 3, "{}", content);
+    assert!(
+        content.contains("12 log lines were dropped due to the limit for rated"),
+        "{}",
+        content
+    );
+}
@@ -0,0 +1,129 @@
+#[cfg(feature = "specfile")]
+extern crate chrono;
+#[cfg(feature = "specfile")]
+extern crate flexi_logger;
+#[cfg(feature = "specfile")]
+extern crate glob;
+#[cfg(feature = "specfile")]
+#[macro_use]
+extern crate log;
+
+#[cfg(feature = "specfile")]
+mod logger_config {
+    use chrono::Local;
+    use flexi_logger::{Logger, LoggerConfig};
+    use glob::glob;
+    use std::fs;
+
+    #[test]
+    fn test_logger_config() {
+        let directory = directory("toml");
+        let config_file = format!("{}/logger.toml", directory);
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            &config_file,
+            format!(
+                "spec = 'info'\n\
+                 log_to_file = true\n\
+                 format = 'opt'\n\
+                 \n\
+                 [file]\n\
+                 directory = '{dir}'\n\
+                 discriminant = 'main'\n\
+                 rotate_over_size = 100_000\n\
+                 max_backup = 2\n\
+                 max_file_age = '7d'\n\
+                 \n\
+                 [writers.alerts]\n\
+                 spec = 'warn'\n\
+                 format_pattern = '{{level}} {{msg}}'\n\
+                 \n\
+                 [writers.alerts.file]\n\
+                 directory = '{dir}'\n",
+                dir = directory
+            ),
+        )
+        .unwrap();
+
+        let config = LoggerConfig::from_file(&config_file).unwrap();
+        Logger::from_config(&config)
+            .unwrap()
+            .start()
+            .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+        error!(target: "{_Default,alerts}", "This is an error message");
+        warn!("This is a warning");
+        info!(target: "{_Default,alerts}", "This is an info message");
+        debug!("This is a debug message - you must not see it!");
+        log::logger().flush();
+
+        let main_lines = read_lines(&format!("{}/*_main_r00001.log", directory));
+        assert_eq!(main_lines.len(), 3);
+        assert!(main_lines[0].starts_with("[20"));
+        assert!(main_lines[0].contains("] ERROR [tests/test_logger_config.rs:"));
+        assert!(main_lines[0].ends_with("] This is an error message"));
+        assert_eq!(
+            read_lines(&format!("{}/*.alerts", directory)),
+            vec!["ERROR This is an error message"]
+        );
+    }
+
+    #[test]
+    fn test_logger_config_formats() {
+        let directory = directory("formats");
+        fs::create_dir_all(&directory).unwrap();
+
+        let json_file = format!("{}/logger.json", directory);
+        fs::write(
+            &json_file,
+            "{ \"spec\": \"debug\", \"duplicate_to_stderr\": \"warn\", \
+             \"file\": { \"rotate_over_age\": \"day\", \"async_capacity\": 100 } }",
+        )
+        .unwrap();
+        let config = LoggerConfig::from_file(&json_file).unwrap();
+        assert_eq!(config.spec, Some("debug".to_string()));
+        assert_eq!(config.file.async_capacity, Some(100));
+
+        let yaml_file = format!("{}/logger.yaml", directory);
+        fs::write(
+            &yaml_file,
+            "log_to_stdout: true\nwriters:\n  security:\n    spec: info\n    file:\n      suffix: sec\n",
+        ).unwrap();
+        let config = LoggerConfig::from_file(&yaml_file).unwrap();
+        assert!(config.log_to_stdout);
+        assert_eq!(
+            config.writers["security"].file.suffix,
+            Some("sec".to_string())
+        );
+
+        // unknown entries and invalid values are rejected
+        fs::write(&yaml_file, "log_to_flie: true\n").unwrap();
+        assert!(LoggerConfig::from_file(&yaml_file).is_err());
+        fs::write(&yaml_file, "duplicate_to_stderr: sometimes\n").unwrap();
+        let config = LoggerConfig::from_file(&yaml_file).unwrap();
+        assert!(Logger::from_config(&config).is_err());
+        fs::write(&yaml_file, "file:\n  max_file_age: a week\n").unwrap();
+        let config = LoggerConfig::from_file(&yaml_file).unwrap();
+        assert!(Logger::from_config(&config).is_err());
+        fs::write(&yaml_file, "file:\n  max_file_age: 18446744073709551615d\n").unwrap();
+        let config = LoggerConfig::from_file(&yaml_file).unwrap();
+        assert!(Logger::from_config(&config).is_err());
+    }
+
+    fn directory(name: &str) -> String {
+        format!(
+            "./log_files/logger_config/{}/{}",
+            name,
+            Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+        )
+    }
+
+    fn read_lines(pattern: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        for globresult in glob(pattern).unwrap() {
+            let content = fs::read_to_string(globresult.unwrap()).unwrap();
+            lines.extend(content.lines().map(|l| l.to_string()));
+        }
+        lines
+    }
+}
@@ -0,0 +1,88 @@
+extern crate flexi_logger;
+extern crate log;
+
+use flexi_logger::{LogSpecBuilder, LogSpecification};
+use log::{Level, LevelFilter};
+
+#[test]
+fn test_wildcard() {
+    let spec = LogSpecification::parse("info, *::db = debug");
+    assert!(spec.enabled(Level::Debug, "crate1::db"));
+    assert!(spec.enabled(Level::Debug, "crate2::mod_a::db"));
+    assert!(spec.enabled(Level::Debug, "crate2::db::pool"));
+    assert!(!spec.enabled(Level::Debug, "crate2::dbx"));
+    assert!(!spec.enabled(Level::Debug, "crate2::mod_a"));
+    assert!(spec.enabled(Level::Info, "crate2::mod_a"));
+}
+
+#[test]
+fn test_wildcard_in_the_middle() {
+    let spec = LogSpecification::parse("warn, crate*::net = trace");
+    assert!(spec.enabled(Level::Trace, "crate1::net"));
+    assert!(spec.enabled(Level::Trace, "crate2::mod_a::net::tcp"));
+    assert!(!spec.enabled(Level::Info, "other::net"));
+}
+
+#[test]
+fn test_regex() {
+    let spec = LogSpecification::parse("info, ^crate[1-2]::db$ = trace");
+    assert!(spec.enabled(Level::Trace, "crate1::db"));
+    assert!(spec.enabled(Level::Trace, "crate2::db"));
+    assert!(!spec.enabled(Level::Trace, "crate1::db::pool"));
+    assert!(!spec.enabled(Level::Trace, "crate3::db"));
+
+    // an invalid regex is ignored
+    let spec = LogSpecification::parse("info, ^crate(::db = trace");
+    assert!(!spec.enabled(Level::Trace, "crate(::db"));
+}
+
+#[test]
+fn test_precedence() {
+    let spec = LogSpecification::parse("info, crate1 = warn, *::db = debug, crate1::db = trace");
+    assert!(!spec.enabled(Level::Info, "crate1::mod_a"));
+    assert!(spec.enabled(Level::Debug, "crate2::db"));
+    assert!(!spec.enabled(Level::Trace, "crate2::db"));
+    assert!(spec.enabled(Level::Trace, "crate1::db"));
+
+    // the wildcard matches a longer part of the module path than `crate1`
+    let spec = LogSpecification::parse("info, crate1 = warn, *::db = debug");
+    assert!(spec.enabled(Level::Debug, "crate1::db"));
+    assert!(!spec.enabled(Level::Info, "crate1::mod_a"));
+
+    // `crate1::db::pool` matches a longer part of the module path than `*::db`
+    let spec = LogSpecification::parse("info, *::db = debug, crate1::db::pool = error");
+    assert!(!spec.enabled(Level::Warn, "crate1::db::pool"));
+    assert!(spec.enabled(Level::Debug, "crate1::db::cache"));
+}
+
+#[test]
+fn test_builder() {
+    let spec = LogSpecBuilder::new()
+        .default(LevelFilter::Info)
+        .module("*::db", LevelFilter::Debug)
+        .build();
+    assert!(spec.enabled(Level::Debug, "crate1::db"));
+    assert!(!spec.enabled(Level::Debug, "crate1::mod_a"));
+}
+
+#[cfg(feature = "specfile")]
+#[test]
+fn test_specfile() {
+    use std::fs;
+
+    let directory = "log_files/module_patterns";
+    fs::create_dir_all(directory).unwrap();
+    let specfile = format!("{}/logspec.toml", directory);
+    fs::write(
+        &specfile,
+        "global_level = 'info'\n\
+         [modules]\n\
+         '*::db' = 'debug'\n\
+         '^crate[12]::net$' = 'trace'\n",
+    )
+    .unwrap();
+    let spec = LogSpecification::file(&specfile).unwrap();
+    assert!(spec.enabled(Level::Debug, "crate1::db"));
+    assert!(spec.enabled(Level::Trace, "crate2::net"));
+    assert!(!spec.enabled(Level::Debug, "crate1::mod_a"));
+}
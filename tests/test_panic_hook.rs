@@ -0,0 +1,52 @@
+extern crate chrono;
+extern crate flexi_logger;
+extern crate glob;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::Logger;
+use glob::glob;
+use std::fs;
+use std::thread;
+
+#[test]
+fn test_panic_hook() {
+    let directory = format!(
+        "./log_files/panic_hook/{}",
+        Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+    );
+    Logger::with_str("info")
+        .log_to_file()
+        .directory(directory.clone())
+        .log_panics_with_backtrace()
+        .start()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    info!("before the panic");
+    let result = thread::Builder::new()
+        .name("worker".to_string())
+        .spawn(|| {
+            panic!("worker gave up after {} attempts", 3);
+        })
+        .unwrap()
+        .join();
+    assert!(result.is_err());
+    info!("after the panic");
+
+    let mut content = String::new();
+    for globresult in glob(&format!("{}/*.log", directory)).unwrap() {
+        content.push_str(&fs::read_to_string(globresult.unwrap()).unwrap());
+    }
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines[0], "INFO [test_panic_hook] before the panic");
+    assert!(lines[1].starts_with(
+        "ERROR [panic] thread 'worker' panicked at 'worker gave up after 3 attempts', "
+    ));
+    assert!(lines[1].contains("tests/test_panic_hook.rs:"));
+    assert_eq!(lines[2], "stack backtrace:");
+    assert_eq!(
+        lines.last().unwrap(),
+        &"INFO [test_panic_hook] after the panic"
+    );
+}
@@ -0,0 +1,28 @@
+extern crate chrono;
+extern crate flexi_logger;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::Logger;
+use std::panic::{self, AssertUnwindSafe};
+
+// A panic while the log file is locked by the panicking thread must not deadlock.
+#[test]
+fn test_panic_hook_locked() {
+    let handle = Logger::with_str("info")
+        .log_to_file()
+        .directory(format!(
+            "./log_files/panic_hook_locked/{}",
+            Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+        ))
+        .log_panics()
+        .start_reconfigurable()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    info!("This is an info message");
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        handle.validate_logs(&[("WARN", "test_panic_hook_locked", "not logged")])
+    }));
+    assert!(result.is_err());
+}
@@ -0,0 +1,57 @@
+extern crate chrono;
+extern crate flexi_logger;
+extern crate glob;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::{Logger, StatisticsHandle};
+use glob::glob;
+use std::fs;
+use std::thread;
+
+#[test]
+fn test_panic_hook_module_spec() {
+    let directory = format!(
+        "./log_files/panic_hook_module_spec/{}",
+        Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+    );
+    Logger::with_str("test_panic_hook_module_spec=info")
+        .log_to_file()
+        .directory(directory.clone())
+        .log_panics()
+        .start()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    info!("before the panic");
+    let result = thread::Builder::new()
+        .name("worker".to_string())
+        .spawn(|| {
+            panic!("worker gave up");
+        })
+        .unwrap()
+        .join();
+    assert!(result.is_err());
+    info!("after the panic");
+
+    // the panic is logged like other records, only the log specification does not apply
+    let statistics = StatisticsHandle::current().unwrap().statistics();
+    assert_eq!(statistics.records.error, 1);
+    assert_eq!(statistics.records.info, 2);
+
+    let mut content = String::new();
+    for globresult in glob(&format!("{}/*.log", directory)).unwrap() {
+        content.push_str(&fs::read_to_string(globresult.unwrap()).unwrap());
+    }
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(
+        lines[0],
+        "INFO [test_panic_hook_module_spec] before the panic"
+    );
+    assert!(lines[1].starts_with("ERROR [panic] thread 'worker' panicked at 'worker gave up', "));
+    assert_eq!(
+        lines[2],
+        "INFO [test_panic_hook_module_spec] after the panic"
+    );
+}
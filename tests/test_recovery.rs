@@ -0,0 +1,108 @@
+extern crate chrono;
+extern crate flexi_logger;
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::writers::{FileLogWriter, LogWriter};
+use log::{Level, Record};
+use std::fs;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn test_recovery_on_flush() {
+    let directory = directory("flush");
+    let writer = FileLogWriter::builder()
+        .directory(directory.clone())
+        .rotate_over_size(1)
+        .recover_from_io_errors(10_000)
+        .instantiate()
+        .unwrap();
+
+    write_lines(&writer, 1..4);
+    fs::remove_dir_all(&directory).unwrap();
+    write_lines(&writer, 4..9);
+    writer.flush().unwrap();
+    write_lines(&writer, 9..10);
+    writer.flush().unwrap();
+
+    let content = read_files(&directory);
+    assert!(content.contains("writing to this file failed from"));
+    assert!(content.contains("the 5 log lines of that time that were kept in memory follow\n"));
+    assert!(!content.contains("written to stderr instead"));
+    assert!(!content.contains("line 1\n"));
+    assert_in_order(&content, 4..10);
+}
+
+#[test]
+fn test_recovery_with_full_buffer() {
+    let directory = directory("full_buffer");
+    let writer = FileLogWriter::builder()
+        .directory(directory.clone())
+        .rotate_over_size(1)
+        .recover_from_io_errors(60)
+        .instantiate()
+        .unwrap();
+
+    write_lines(&writer, 1..4);
+    fs::remove_dir_all(&directory).unwrap();
+    write_lines(&writer, 4..9);
+    // the next log call after the retry interval opens the file again
+    thread::sleep(Duration::from_millis(1100));
+    write_lines(&writer, 9..10);
+    writer.flush().unwrap();
+
+    let content = read_files(&directory);
+    assert!(content.contains(
+        "the 2 log lines of that time that were kept in memory follow, \
+         3 log lines were written to stderr instead\n"
+    ));
+    assert_in_order(&content, 4..6);
+    assert!(!content.contains("line 6\n"));
+    assert!(content.contains("line 9\n"));
+}
+
+fn write_lines(writer: &FileLogWriter, lines: std::ops::Range<usize>) {
+    for i in lines {
+        writer
+            .write(
+                &Record::builder()
+                    .args(format_args!("line {}", i))
+                    .level(Level::Info)
+                    .module_path(Some("test_recovery"))
+                    .build(),
+            )
+            .unwrap();
+    }
+}
+
+fn assert_in_order(content: &str, lines: std::ops::Range<usize>) {
+    let mut position = 0;
+    for i in lines {
+        let line = format!("line {}\n", i);
+        position += content[position..]
+            .find(&line)
+            .unwrap_or_else(|| panic!("{:?} is missing or out of order", line));
+    }
+}
+
+// Returns the content of all log files of the directory, in the order of their names.
+fn read_files(directory: &str) -> String {
+    let mut paths: Vec<_> = fs::read_dir(directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| fs::read_to_string(path).unwrap())
+        .collect()
+}
+
+fn directory(test: &str) -> String {
+    format!(
+        "log_files/recovery/{}/{}",
+        test,
+        Local::now().format("%Y-%m-%d_%H-%M-%S")
+    )
+}
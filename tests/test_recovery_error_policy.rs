@@ -0,0 +1,59 @@
+extern crate chrono;
+extern crate flexi_logger;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::writers::FileLogWriter;
+use flexi_logger::{ErrorPolicy, Logger};
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_recovery_error_policy() {
+    let directory = format!(
+        "log_files/recovery_error_policy/{}",
+        Local::now().format("%Y-%m-%d_%H-%M-%S")
+    );
+    let errors = Arc::new(Mutex::new(Vec::<String>::new()));
+    let errors_clone = Arc::clone(&errors);
+    let alerts = FileLogWriter::builder()
+        .directory(format!("{}/alerts", directory))
+        .suffix("alerts")
+        .rotate_over_size(1)
+        .recover_from_io_errors(10_000)
+        .instantiate()
+        .unwrap();
+    let handle = Logger::with_str("info")
+        .log_to_file()
+        .directory(directory.clone())
+        .rotate_over_size(1)
+        .recover_from_io_errors(10_000)
+        .add_writer("alerts", Box::new(alerts))
+        .error_policy(ErrorPolicy::Callback(Box::new(move |e| {
+            // the error handling may log
+            info!("error reported: {}", e);
+            errors_clone.lock().unwrap().push(e.to_string());
+        })))
+        .start_reconfigurable()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    info!("line 1");
+    warn!(target: "{alerts}", "alert 1");
+    fs::remove_dir_all(&directory).unwrap();
+    for i in 2..5 {
+        info!("line {}", i);
+    }
+    // the additional writer reports the start of its failure in the same way
+    warn!(target: "{alerts}", "alert 2");
+    log::logger().flush();
+
+    let errors = errors.lock().unwrap();
+    assert_eq!(errors.len(), 2, "{:?}", *errors);
+    assert!(errors[0].starts_with("writing log line to primary_writer failed with "));
+    assert!(errors[0].contains("log lines are kept in memory until writing to "));
+    assert!(errors[1].starts_with("writing log line to alerts failed with "));
+    let statistics = handle.statistics();
+    assert_eq!(statistics.write_errors, 1);
+    assert_eq!(statistics.writers["alerts"].write_errors, 1);
+}
@@ -0,0 +1,76 @@
+extern crate chrono;
+extern crate flexi_logger;
+extern crate glob;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::Logger;
+use glob::glob;
+use std::fs;
+
+#[test]
+fn test_redirect() {
+    let directory = format!(
+        "./log_files/redirect/{}",
+        Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+    );
+    let dir_1 = format!("{}/first", directory);
+    let dir_2 = format!("{}/second", directory);
+
+    let mut handle = Logger::with_str("info")
+        .log_to_file()
+        .directory(dir_1.clone())
+        .start_reconfigurable()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    info!("written to the first directory");
+
+    handle
+        .reconfigure_file_output(|flwb| flwb.directory(dir_2.clone()).discriminant("dscr"))
+        .unwrap_or_else(|e| panic!("Redirecting the log output failed with {}", e));
+    info!("written to the second directory");
+
+    handle
+        .disable_file_output()
+        .unwrap_or_else(|e| panic!("Disabling the file output failed with {}", e));
+    info!("written to stderr");
+
+    // the directory and the discriminant are kept, only the rotation changes
+    handle
+        .reconfigure_file_output(|flwb| flwb.rotate_over_size(100))
+        .unwrap_or_else(|e| panic!("Redirecting the log output failed with {}", e));
+    for idx in 0..5 {
+        info!(
+            "written to a rotated file in the second directory ({})",
+            idx
+        );
+    }
+    log::logger().flush();
+
+    assert_eq!(
+        read_lines(&format!("{}/*.log", dir_1)),
+        vec!["INFO [test_redirect] written to the first directory"]
+    );
+    assert_eq!(
+        read_lines(&format!("{}/*_dscr_[0-9]*[0-9].log", dir_2)),
+        vec!["INFO [test_redirect] written to the second directory"]
+    );
+    let rotated_files = glob(&format!("{}/*_dscr_r[0-9]*.log", dir_2))
+        .unwrap()
+        .count();
+    assert!(rotated_files > 1, "found {} rotated files", rotated_files);
+    assert_eq!(
+        read_lines(&format!("{}/*_dscr_r[0-9]*.log", dir_2)).len(),
+        5
+    );
+}
+
+fn read_lines(pattern: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    for globresult in glob(pattern).unwrap() {
+        let content = fs::read_to_string(globresult.unwrap()).unwrap();
+        lines.extend(content.lines().map(|l| l.to_string()));
+    }
+    lines
+}
@@ -0,0 +1,148 @@
+extern crate chrono;
+extern crate filetime;
+extern crate flexi_logger;
+extern crate glob;
+extern crate log;
+
+use chrono::Local;
+use filetime::FileTime;
+use flexi_logger::writers::{FileLogWriter, LogWriter};
+use glob::glob;
+use log::{Level, Record};
+use std::env;
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+const ONE_DAY: u64 = 24 * 60 * 60;
+
+#[test]
+fn test_retention_max_file_age() {
+    let directory = define_directory("age");
+    fs::create_dir_all(&directory).unwrap();
+    let progname = progname();
+    let ten_days_ago = SystemTime::now() - Duration::from_secs(10 * ONE_DAY);
+    let old_files = [format!("{}_2018-09-01_10-00-00.log", progname)];
+    let kept_files = [
+        // not old
+        format!("{}_2018-09-10_10-00-00.log", progname),
+        // other discriminants, other program, other suffix
+        format!("{}_Alert_2018-09-01_10-00-00.log", progname),
+        format!("{}_1_2018-09-01_10-00-00.log", progname),
+        "other_2018-09-01_10-00-00.log".to_string(),
+        format!("{}_2018-09-01_10-00-00.txt", progname),
+        // named by another configuration
+        format!("{}_2018-09-02_10-00-00_r00001.log", progname),
+        format!("{}.log", progname),
+        // still being compressed
+        format!("{}_2018-09-03_10-00-00.log", progname),
+        format!("{}_2018-09-03_10-00-00.log.gz", progname),
+    ];
+    for name in old_files.iter().chain(kept_files[1..].iter()) {
+        create_file(&directory, name, ten_days_ago);
+    }
+    create_file(&directory, &kept_files[0], SystemTime::now());
+
+    let writer = FileLogWriter::builder()
+        .directory(directory.clone())
+        .max_file_age(Duration::from_secs(5 * ONE_DAY))
+        .instantiate()
+        .unwrap_or_else(|e| panic!("FileLogWriter initialization failed with {}", e));
+    write_lines(&writer, 3);
+    writer.flush().unwrap();
+
+    for name in &old_files {
+        assert!(
+            !Path::new(&directory).join(name).exists(),
+            "{} exists",
+            name
+        );
+    }
+    for name in &kept_files {
+        assert!(
+            Path::new(&directory).join(name).exists(),
+            "{} was removed",
+            name
+        );
+    }
+    // the current file, besides the kept files
+    assert_eq!(
+        list_files(&format!("{}/{}_*.log", directory, progname)).len(),
+        6
+    );
+}
+
+#[test]
+fn test_retention_max_total_size() {
+    let directory = define_directory("size");
+    let writer = FileLogWriter::builder()
+        .directory(directory.clone())
+        .rotate_over_size(200)
+        .max_total_size(1000)
+        .instantiate()
+        .unwrap_or_else(|e| panic!("FileLogWriter initialization failed with {}", e));
+    write_lines(&writer, 100);
+    writer.flush().unwrap();
+
+    let files = list_files(&format!("{}/*.log", directory));
+    let total_size: u64 = files
+        .iter()
+        .map(|path| fs::metadata(path).unwrap().len())
+        .sum();
+    // the retention is evaluated at rotation, so the current file can add up
+    // to the rotation size (plus one line) to the total size
+    assert!(total_size < 1000 + 300, "total size is {}", total_size);
+    assert!(files.len() >= 4, "too few files: {:?}", files);
+    // the newest files are kept
+    assert!(
+        files.last().unwrap().ends_with("_r00020.log"),
+        "{:?}",
+        files
+    );
+}
+
+fn create_file(directory: &str, name: &str, modified: SystemTime) {
+    let path = Path::new(directory).join(name);
+    File::create(&path).unwrap();
+    filetime::set_file_mtime(&path, FileTime::from_system_time(modified)).unwrap();
+}
+
+fn write_lines(writer: &FileLogWriter, count: usize) {
+    for idx in 0..count {
+        writer
+            .write(
+                &Record::builder()
+                    .args(format_args!("this is log line number {}", idx))
+                    .level(Level::Info)
+                    .module_path(Some("test_retention"))
+                    .build(),
+            )
+            .unwrap();
+    }
+}
+
+fn list_files(pattern: &str) -> Vec<String> {
+    let mut files: Vec<String> = glob(pattern)
+        .unwrap()
+        .map(|globresult| globresult.unwrap().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    files
+}
+
+fn progname() -> String {
+    let arg0 = env::args().next().unwrap();
+    Path::new(&arg0)
+        .file_stem()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn define_directory(name: &str) -> String {
+    format!(
+        "./log_files/retention/{}/{}",
+        name,
+        Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+    )
+}
@@ -0,0 +1,122 @@
+extern crate chrono;
+extern crate flexi_logger;
+extern crate glob;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::writers::{LogWriter, RingBufferWriter};
+use flexi_logger::Logger;
+use glob::glob;
+use log::{Level, Record};
+use std::fs::{self, File};
+use std::io::Read;
+
+#[test]
+fn test_ring_buffer_limits() {
+    let directory = define_directory("limits");
+    fs::create_dir_all(&directory).unwrap();
+
+    let ring_buffer = RingBufferWriter::builder()
+        .max_lines(3)
+        .level_filter(log::LevelFilter::Debug)
+        .instantiate();
+    for idx in 0..10 {
+        write_line(&ring_buffer, Level::Debug, idx);
+    }
+    write_line(&ring_buffer, Level::Trace, 10);
+    let dump_file = format!("{}/lines.dump", directory);
+    ring_buffer.dump_to_file(&dump_file).unwrap();
+    assert_eq!(
+        read_file(&dump_file),
+        "FlexiLogger: dump of 3 buffered log lines:\n\
+         DEBUG [test_ring_buffer] line 7\n\
+         DEBUG [test_ring_buffer] line 8\n\
+         DEBUG [test_ring_buffer] line 9\n\
+         FlexiLogger: end of dump\n"
+    );
+
+    // each line has 33 bytes
+    let ring_buffer = RingBufferWriter::builder()
+        .max_bytes(70)
+        .dump_file(format!("{}/bytes.dump", directory))
+        .instantiate();
+    for idx in 10..20 {
+        write_line(&ring_buffer, Level::Trace, idx);
+    }
+    ring_buffer.dump();
+    // the dump emptied the buffer
+    ring_buffer.dump();
+    assert_eq!(
+        read_file(&format!("{}/bytes.dump", directory)),
+        "FlexiLogger: dump of 2 buffered log lines:\n\
+         TRACE [test_ring_buffer] line 18\n\
+         TRACE [test_ring_buffer] line 19\n\
+         FlexiLogger: end of dump\n\
+         FlexiLogger: dump of 0 buffered log lines:\n\
+         FlexiLogger: end of dump\n"
+    );
+}
+
+#[test]
+fn test_ring_buffer_dump_on_error() {
+    let directory = define_directory("logger");
+    Logger::with_str("info")
+        .log_to_file()
+        .directory(directory.clone())
+        .ring_buffer(RingBufferWriter::builder().dump_on_error().instantiate())
+        .start()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    info!("This is an info message");
+    debug!("This is a debug message");
+    trace!("This is a trace message");
+    error!("This is an error message");
+    debug!("This is a debug message that is not dumped");
+    log::logger().flush();
+
+    let path = glob(&format!("{}/*.log", directory))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        read_file(&path.to_string_lossy()),
+        "INFO [test_ring_buffer] This is an info message\n\
+         FlexiLogger: dump of 3 buffered log lines:\n\
+         INFO [test_ring_buffer] This is an info message\n\
+         DEBUG [test_ring_buffer] This is a debug message\n\
+         TRACE [test_ring_buffer] This is a trace message\n\
+         FlexiLogger: end of dump\n\
+         ERROR [test_ring_buffer] This is an error message\n"
+    );
+}
+
+fn write_line(writer: &RingBufferWriter, level: Level, idx: usize) {
+    writer
+        .write(
+            &Record::builder()
+                .args(format_args!("line {}", idx))
+                .level(level)
+                .module_path(Some("test_ring_buffer"))
+                .build(),
+        )
+        .unwrap();
+}
+
+fn read_file(path: &str) -> String {
+    let mut content = String::new();
+    File::open(path)
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    content
+}
+
+fn define_directory(name: &str) -> String {
+    format!(
+        "./log_files/ring_buffer/{}/{}",
+        name,
+        Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+    )
+}
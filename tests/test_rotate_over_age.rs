@@ -0,0 +1,104 @@
+extern crate chrono;
+extern crate flexi_logger;
+extern crate glob;
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::writers::{FileLogWriter, LogWriter};
+use flexi_logger::Age;
+use glob::glob;
+use log::{Level, Record};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn test_rotate_over_age_day() {
+    let directory = define_directory("day");
+    let writer = FileLogWriter::builder()
+        .directory(directory.clone())
+        .rotate_over_age(Age::Day)
+        .instantiate()
+        .unwrap_or_else(|e| panic!("FileLogWriter initialization failed with {}", e));
+    write_lines(&writer, 3);
+    writer.flush().unwrap();
+
+    let files = list_files(&directory);
+    assert_eq!(files.len(), 1);
+    assert!(files[0].ends_with(&format!("_{}.log", Local::now().format("%Y-%m-%d"))));
+}
+
+#[test]
+fn test_rotate_over_age_restart() {
+    let directory = define_directory("restart");
+    for _ in 0..2 {
+        // each writer stands for a run of the program within the same day
+        let writer = FileLogWriter::builder()
+            .directory(directory.clone())
+            .rotate_over_age(Age::Day)
+            .instantiate()
+            .unwrap_or_else(|e| panic!("FileLogWriter initialization failed with {}", e));
+        write_lines(&writer, 3);
+        writer.flush().unwrap();
+    }
+
+    let files = list_files(&directory);
+    assert_eq!(files.len(), 1);
+    let content = std::fs::read_to_string(&files[0]).unwrap();
+    assert_eq!(content.lines().count(), 6);
+}
+
+#[test]
+fn test_rotate_over_age_and_size() {
+    let directory = define_directory("every");
+    let writer = FileLogWriter::builder()
+        .directory(directory.clone())
+        .rotate_over_age(Age::Every(Duration::from_secs(1)))
+        .rotate_over_size(2000)
+        .instantiate()
+        .unwrap_or_else(|e| panic!("FileLogWriter initialization failed with {}", e));
+
+    // the size limit is hit twice within the first period
+    write_lines(&writer, 100);
+    thread::sleep(Duration::from_millis(1100));
+    // the period is over, so this starts a new file
+    write_lines(&writer, 1);
+    writer.flush().unwrap();
+
+    let files = list_files(&directory);
+    assert_eq!(files.len(), 4, "unexpected files: {:?}", files);
+    assert!(files[0].ends_with("_r00001.log"));
+    assert!(files[1].ends_with("_r00002.log"));
+    assert!(files[2].ends_with("_r00003.log"));
+    assert!(files[3].ends_with("_r00001.log"));
+}
+
+fn write_lines(writer: &FileLogWriter, count: usize) {
+    for idx in 0..count {
+        writer
+            .write(
+                &Record::builder()
+                    .args(format_args!("this is log line number {}", idx))
+                    .level(Level::Info)
+                    .module_path(Some("test_rotate_over_age"))
+                    .build(),
+            )
+            .unwrap();
+    }
+}
+
+fn list_files(directory: &str) -> Vec<String> {
+    let mut files: Vec<String> = glob(&format!("{}/*.log", directory))
+        .unwrap()
+        .map(|globresult| globresult.unwrap().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    files
+}
+
+fn define_directory(name: &str) -> String {
+    format!(
+        "./log_files/age/{}/{}",
+        name,
+        Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+    )
+}
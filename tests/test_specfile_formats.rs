@@ -0,0 +1,86 @@
+#[cfg(feature = "specfile")]
+extern crate chrono;
+#[cfg(feature = "specfile")]
+extern crate flexi_logger;
+#[cfg(feature = "specfile")]
+extern crate log;
+
+#[cfg(feature = "specfile")]
+mod specfile_formats {
+    use chrono::Local;
+    use flexi_logger::LogSpecification;
+    use log::Level;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_json_specfile() {
+        check_specfile_format("json");
+    }
+
+    #[test]
+    fn test_yaml_specfile() {
+        check_specfile_format("yaml");
+    }
+
+    #[test]
+    fn test_yml_specfile() {
+        check_specfile_format("yml");
+    }
+
+    #[test]
+    fn test_unsupported_suffix() {
+        let specfile = PathBuf::from(format!("{}/logspec.ini", directory("ini")));
+        assert!(LogSpecification::parse("info")
+            .ensure_specfile_is_valid(&specfile)
+            .is_err());
+    }
+
+    // Creates the initial specfiles for a spec with and without module filters,
+    // reads them back, and then reads an edited specfile.
+    fn check_specfile_format(suffix: &str) {
+        let directory = directory(suffix);
+
+        let specfile = PathBuf::from(format!("{}/initial.{}", directory, suffix));
+        LogSpecification::parse("warn")
+            .ensure_specfile_is_valid(&specfile)
+            .unwrap();
+        let spec = LogSpecification::file(&specfile).unwrap();
+        assert!(spec.enabled(Level::Warn, "mod1"));
+        assert!(!spec.enabled(Level::Info, "mod1"));
+
+        let specfile = PathBuf::from(format!("{}/initial_with_modules.{}", directory, suffix));
+        LogSpecification::parse("info, mod1 = debug, mod2::mod3 = error")
+            .ensure_specfile_is_valid(&specfile)
+            .unwrap();
+        let spec = LogSpecification::file(&specfile).unwrap();
+        assert!(spec.enabled(Level::Info, "mod2"));
+        assert!(spec.enabled(Level::Debug, "mod1::mod4"));
+        assert!(!spec.enabled(Level::Warn, "mod2::mod3"));
+        assert!(spec.text_filter().is_none());
+
+        let specfile = PathBuf::from(format!("{}/edited.{}", directory, suffix));
+        let content = if suffix == "json" {
+            "{ \"global_level\": \"error\", \"global_pattern\": \"Foo\", \
+             \"modules\": { \"mod1\": \"trace\" } }"
+        } else {
+            "global_level: error\nglobal_pattern: Foo\nmodules:\n  mod1: trace\n"
+        };
+        fs::write(&specfile, content).unwrap();
+        let spec = LogSpecification::file(&specfile).unwrap();
+        assert!(!spec.enabled(Level::Warn, "mod2"));
+        assert!(spec.enabled(Level::Trace, "mod1"));
+        assert_eq!(spec.text_filter().as_ref().unwrap().as_str(), "Foo");
+
+        fs::write(&specfile, "global_level: [").unwrap();
+        assert!(LogSpecification::file(&specfile).is_err());
+    }
+
+    fn directory(suffix: &str) -> String {
+        format!(
+            "./log_files/specfile_formats/{}/{}",
+            suffix,
+            Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+        )
+    }
+}
@@ -0,0 +1,71 @@
+extern crate chrono;
+extern crate flexi_logger;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::writers::FileLogWriter;
+use flexi_logger::{LogSpecification, Logger, StatisticsHandle};
+use std::time::Duration;
+
+#[test]
+fn test_statistics() {
+    let directory = format!(
+        "log_files/statistics/{}",
+        Local::now().format("%Y-%m-%d_%H-%M-%S")
+    );
+    let alerts = FileLogWriter::builder()
+        .directory(directory.clone())
+        .suffix("alerts")
+        .suppress_timestamp()
+        .instantiate()
+        .unwrap();
+    let handle = Logger::with_str("info, rated = info@sample=2/!secret")
+        .log_to_file()
+        .directory(directory)
+        .suppress_timestamp()
+        .deduplicate(Duration::from_secs(60))
+        .add_writer_with_spec("alerts", Box::new(alerts), LogSpecification::parse("warn@sample=2"))
+        .start_reconfigurable()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    error!("This is an error message");
+    warn!("This is a warning");
+    info!("This is an info message");
+    info!("This is an info message");
+    debug!("This is a debug message - you must not see it!");
+    info!("This is a secret info message - you must not see it!");
+    for i in 0..4 {
+        info!(target: "rated", "This is rated info message {}", i);
+    }
+    error!(target: "{alerts}", "This is an alert");
+    warn!(target: "{alerts}", "This is a warning alert");
+    warn!(target: "{alerts}", "This is a sampled warning alert");
+    info!(target: "{alerts}", "This is an info alert - you must not see it!");
+    log::logger().flush();
+
+    let statistics = handle.statistics();
+    assert_eq!(statistics.records.error, 1);
+    assert_eq!(statistics.records.warn, 1);
+    assert_eq!(statistics.records.info, 3);
+    assert_eq!(statistics.records.total(), 5);
+    assert_eq!(statistics.suppressed_by_spec, 1);
+    assert_eq!(statistics.suppressed_by_text_filter, 1);
+    assert_eq!(statistics.suppressed_by_limit, 2);
+    assert_eq!(statistics.suppressed_as_duplicate, 1);
+    assert_eq!(statistics.write_errors, 0);
+    assert_eq!(statistics.flush_errors, 0);
+    let file = statistics.file.unwrap();
+    assert!(file.bytes_written > 0);
+    assert_eq!(file.rotations, 0);
+
+    let alerts = &statistics.writers["alerts"];
+    assert_eq!(alerts.records, 2);
+    assert_eq!(alerts.suppressed_by_spec, 1);
+    assert_eq!(alerts.suppressed_by_limit, 1);
+    assert_eq!(alerts.write_errors, 0);
+    assert!(alerts.file.unwrap().bytes_written > 0);
+
+    let current = StatisticsHandle::current().unwrap().statistics();
+    assert_eq!(current.records, statistics.records);
+}
@@ -0,0 +1,77 @@
+extern crate chrono;
+extern crate flexi_logger;
+#[macro_use]
+extern crate log;
+
+use chrono::Local;
+use flexi_logger::{Duplicate, Logger};
+use std::env;
+use std::process::Command;
+
+// Only one logger can be initialized per process, and the output to stdout cannot be
+// captured in the same process, so each test runs itself again as a child process,
+// which does the logging, and verifies what the child writes to stdout.
+const CHILD_MARKER: &str = "FLEXI_LOGGER_TEST_STDOUT_CHILD";
+
+#[test]
+fn test_log_to_stdout() {
+    if env::var(CHILD_MARKER).is_ok() {
+        Logger::with_str("info")
+            .log_to_stdout()
+            .start()
+            .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+        write_logs();
+        return;
+    }
+
+    let (stdout, stderr) = run_in_child("test_log_to_stdout");
+    assert!(stdout.contains("ERROR [test_stdout] This is an error message\n"));
+    assert!(stdout.contains("WARN [test_stdout] This is a warning\n"));
+    assert!(stdout.contains("INFO [test_stdout] This is an info message\n"));
+    assert!(!stdout.contains("This is a debug message"));
+    assert!(!stderr.contains("[test_stdout]"));
+}
+
+#[test]
+fn test_duplicate_to_stdout() {
+    if env::var(CHILD_MARKER).is_ok() {
+        Logger::with_str("info")
+            .log_to_file()
+            .directory(format!(
+                "./log_files/stdout/{}",
+                Local::now().format("%Y-%m-%d_%H-%M-%S%.6f")
+            ))
+            .duplicate_to_stdout(Duplicate::Warn)
+            .start()
+            .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+        write_logs();
+        return;
+    }
+
+    let (stdout, stderr) = run_in_child("test_duplicate_to_stdout");
+    assert!(stdout.contains("ERROR [test_stdout] This is an error message\n"));
+    assert!(stdout.contains("WARN [test_stdout] This is a warning\n"));
+    assert!(!stdout.contains("This is an info message"));
+    assert!(!stderr.contains("[test_stdout]"));
+}
+
+fn write_logs() {
+    error!("This is an error message");
+    warn!("This is a warning");
+    info!("This is an info message");
+    debug!("This is a debug message");
+    log::logger().flush();
+}
+
+fn run_in_child(test_name: &str) -> (String, String) {
+    let output = Command::new(env::current_exe().unwrap())
+        .args([test_name, "--exact", "--nocapture", "--test-threads=1"])
+        .env(CHILD_MARKER, "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "child process failed: {:?}", output);
+    (
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
@@ -0,0 +1,83 @@
+#[cfg(unix)]
+extern crate flexi_logger;
+#[cfg(unix)]
+extern crate log;
+
+#[cfg(unix)]
+use flexi_logger::writers::{LogWriter, SyslogFacility, SyslogProtocol, SyslogWriter};
+#[cfg(unix)]
+use log::{Level, Record};
+#[cfg(unix)]
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+
+#[cfg(unix)]
+#[cfg_attr(unix, test)]
+fn test_syslog_writer() {
+    fs::create_dir_all("./log_files/syslog").unwrap();
+    let path = format!("./log_files/syslog/socket_{}", std::process::id());
+    fs::remove_file(&path).ok();
+    let syslog = UnixDatagram::bind(&path).unwrap();
+
+    let writer = SyslogWriter::builder()
+        .path(&path)
+        .facility(SyslogFacility::Local3)
+        .app_name("test_app")
+        .instantiate()
+        .unwrap_or_else(|e| panic!("SyslogWriter initialization failed with {}", e));
+    write(&writer, Level::Error, "This is an error message");
+    write(&writer, Level::Trace, "This is a trace message");
+
+    // facility 19, severity 3
+    let message = receive(&syslog);
+    assert!(
+        message.starts_with("<155>"),
+        "unexpected message {}",
+        message
+    );
+    assert!(message.contains(&format!(" test_app[{}]: ", std::process::id())));
+    assert!(message.ends_with("ERROR [test_syslog_writer] This is an error message"));
+    // facility 19, severity 7
+    assert!(receive(&syslog).starts_with("<159>"));
+
+    let writer = SyslogWriter::builder()
+        .path(&path)
+        .protocol(SyslogProtocol::Rfc5424)
+        .app_name("test_app")
+        .instantiate()
+        .unwrap_or_else(|e| panic!("SyslogWriter initialization failed with {}", e));
+    write(&writer, Level::Warn, "This is a warning");
+
+    // facility 1, severity 4
+    let message = receive(&syslog);
+    assert!(
+        message.starts_with("<12>1 "),
+        "unexpected message {}",
+        message
+    );
+    assert!(message.contains(&format!(" - test_app {} - - ", std::process::id())));
+    assert!(message.ends_with("WARN [test_syslog_writer] This is a warning"));
+
+    fs::remove_file(&path).ok();
+}
+
+#[cfg(unix)]
+fn write(writer: &SyslogWriter, level: Level, message: &str) {
+    writer
+        .write(
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .level(level)
+                .module_path(Some("test_syslog_writer"))
+                .build(),
+        )
+        .unwrap();
+}
+
+#[cfg(unix)]
+fn receive(syslog: &UnixDatagram) -> String {
+    let mut buf = [0_u8; 1024];
+    let len = syslog.recv(&mut buf).unwrap();
+    String::from_utf8_lossy(&buf[..len]).into_owned()
+}
@@ -0,0 +1,65 @@
+extern crate flexi_logger;
+#[macro_use]
+extern crate log;
+
+use flexi_logger::{detailed_format, LogSpecification, Logger};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn test_temp_spec() {
+    let mut handle = Logger::with_str("info")
+        .format(detailed_format)
+        .log_to_file()
+        .start_reconfigurable()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    info!("This is an info message");
+    debug!("This is a debug message - you must not see it!");
+
+    handle.parse_and_push_temp_spec("debug");
+    debug!("This is a debug message");
+    trace!("This is a trace message - you must not see it!");
+
+    handle.parse_and_push_temp_spec("error");
+    warn!("This is a warning - you must not see it!");
+    assert!(handle
+        .current_spec()
+        .enabled(log::Level::Error, "test_temp_spec"));
+    assert!(!handle
+        .current_spec()
+        .enabled(log::Level::Warn, "test_temp_spec"));
+
+    assert!(handle.pop_temp_spec().is_some());
+    debug!("This is a debug message");
+
+    // the timed specs expire while the other temporary spec is still active,
+    // so popping the latter reactivates the initial spec
+    handle.push_temp_spec_for(LogSpecification::parse("trace"), Duration::from_millis(300));
+    handle.push_temp_spec_for(LogSpecification::parse("info"), Duration::from_millis(100));
+    trace!("This is a trace message - you must not see it!");
+    thread::sleep(Duration::from_millis(200));
+    trace!("This is a trace message");
+    thread::sleep(Duration::from_millis(300));
+    trace!("This is a trace message - you must not see it!");
+    debug!("This is a debug message");
+
+    // a new spec replaces the initial spec, and the temporary spec stays active
+    handle.parse_new_spec("warn");
+    debug!("This is a debug message");
+
+    assert!(handle.pop_temp_spec().is_some());
+    assert!(handle.pop_temp_spec().is_none());
+    info!("This is an info message - you must not see it!");
+    warn!("This is a warning");
+
+    handle.validate_logs(&[
+        ("INFO", "test_temp_spec", "info"),
+        ("DEBUG", "test_temp_spec", "debug"),
+        ("DEBUG", "test_temp_spec", "debug"),
+        ("TRACE", "test_temp_spec", "trace"),
+        ("DEBUG", "test_temp_spec", "debug"),
+        ("DEBUG", "test_temp_spec", "debug"),
+        ("WARN", "test_temp_spec", "warning"),
+    ]);
+}
@@ -0,0 +1,132 @@
+extern crate chrono;
+extern crate flexi_logger;
+#[macro_use]
+extern crate log;
+extern crate regex;
+
+use chrono::Local;
+use flexi_logger::{LogSpecBuilder, LogSpecification, Logger};
+use log::LevelFilter;
+use regex::{Regex, RegexBuilder};
+use std::fs;
+
+#[test]
+fn test_textfilter_exclude() {
+    let directory = directory("logger");
+    Logger::with_str("info/Hello/Bye/!secret")
+        .log_to_file()
+        .directory(directory.clone())
+        .suppress_timestamp()
+        .start()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    error!("Hello, this is an error message");
+    warn!("Hello, this is a secret warning - you must not see it!");
+    info!("Bye, this is an info message");
+    info!("This is an info message - you must not see it!");
+    debug!("Hello, this is a debug message - you must not see it!");
+    log::logger().flush();
+
+    let path = fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension() == Some("log".as_ref()))
+        .unwrap();
+    let content = fs::read_to_string(path).unwrap();
+    assert!(!content.contains("must not see it"));
+    assert_eq!(content.lines().count(), 2);
+}
+
+#[test]
+fn test_parse_exclude_only() {
+    let spec = LogSpecification::parse("info/!heartbeat");
+    assert!(spec.text_filter().is_none());
+    assert_eq!(
+        spec.exclude_text_filter().as_ref().unwrap().as_str(),
+        "heartbeat"
+    );
+    assert!(spec.text_matches("request served"));
+    assert!(!spec.text_matches("heartbeat received"));
+}
+
+#[test]
+fn test_parse_literal_exclamation_mark() {
+    let spec = LogSpecification::parse("info/[!]important");
+    assert!(spec.exclude_text_filter().is_none());
+    assert!(spec.text_matches("!important message"));
+    assert!(!spec.text_matches("important message"));
+}
+
+#[test]
+fn test_parse_invalid_pattern() {
+    let spec = LogSpecification::parse("info/foo/(/!bar/!)");
+    assert_eq!(spec.text_filter().as_ref().unwrap().as_str(), "foo");
+    assert_eq!(spec.exclude_text_filter().as_ref().unwrap().as_str(), "bar");
+}
+
+#[test]
+fn test_builder() {
+    let mut builder = LogSpecBuilder::new();
+    builder
+        .default(LevelFilter::Info)
+        .include_text(Regex::new("foo").unwrap())
+        .include_text(Regex::new("bar").unwrap())
+        .exclude_text(Regex::new("baz").unwrap());
+    let spec = builder.build();
+    assert!(spec.text_matches("foo"));
+    assert!(spec.text_matches("bar"));
+    assert!(!spec.text_matches("qux"));
+    assert!(!spec.text_matches("foo baz"));
+
+    let spec = builder.build_with_textfilter(Some(Regex::new("qux").unwrap()));
+    assert!(spec.text_matches("qux"));
+    assert!(!spec.text_matches("qux baz"));
+}
+
+#[test]
+fn test_builder_keeps_regex_options() {
+    let case_insensitive = |pattern| {
+        RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .unwrap()
+    };
+    let mut builder = LogSpecBuilder::new();
+    builder
+        .default(LevelFilter::Info)
+        .include_text(case_insensitive("foo"))
+        .exclude_text(case_insensitive("baz"));
+    let spec = builder.build_with_textfilter(Some(Regex::new("bar").unwrap()));
+    assert!(spec.text_matches("FOO"));
+    assert!(spec.text_matches("bar"));
+    assert!(!spec.text_matches("BAR"));
+    assert!(!spec.text_matches("foo BAZ"));
+}
+
+#[cfg(feature = "specfile")]
+#[test]
+fn test_specfile_pattern_lists() {
+    let specfile = std::path::PathBuf::from(format!("{}/logspec.toml", directory("specfile")));
+    fs::write(
+        &specfile,
+        "global_level = 'info'\n\
+         global_pattern = ['foo', 'bar']\n\
+         exclude_pattern = 'baz'\n",
+    )
+    .unwrap();
+    let spec = LogSpecification::file(&specfile).unwrap();
+    assert!(spec.text_matches("foo"));
+    assert!(spec.text_matches("bar"));
+    assert!(!spec.text_matches("qux"));
+    assert!(!spec.text_matches("bar baz"));
+}
+
+fn directory(test: &str) -> String {
+    let directory = format!(
+        "log_files/textfilter_exclude/{}/{}",
+        test,
+        Local::now().format("%Y-%m-%d_%H-%M-%S")
+    );
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
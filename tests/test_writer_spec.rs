@@ -0,0 +1,75 @@
+extern crate flexi_logger;
+#[macro_use]
+extern crate log;
+
+use flexi_logger::writers::LogWriter;
+use flexi_logger::{LogSpecification, Logger};
+use log::Record;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_writer_spec() {
+    let alert_writer = MemWriter::default();
+    let sec_writer = MemWriter::default();
+    let mut handle = Logger::with_str("info")
+        .add_writer_with_spec(
+            "Alert",
+            Box::new(alert_writer.clone()),
+            LogSpecification::parse("warn, test_writer_spec::noisy = error"),
+        )
+        .add_writer_with_spec(
+            "Sec",
+            Box::new(sec_writer.clone()),
+            LogSpecification::parse("debug/login"),
+        )
+        .start_reconfigurable()
+        .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
+
+    error!(target: "{Alert}", "alert error");
+    warn!(target: "{Alert}", "alert warning");
+    info!(target: "{Alert}", "alert info - must not be written");
+    noisy::warn_alert();
+    debug!(target: "{Sec}", "login of user karl");
+    debug!(target: "{Sec}", "logout of user karl - must not be written");
+    trace!(target: "{Sec}", "login of user emma - must not be written");
+
+    handle.parse_new_writer_spec("Alert", "info");
+    info!(target: "{Alert}", "alert info");
+    debug!(target: "{Alert}", "alert debug - must not be written");
+
+    assert_eq!(
+        alert_writer.lines(),
+        vec!["ERROR alert error", "WARN alert warning", "INFO alert info",]
+    );
+    assert_eq!(sec_writer.lines(), vec!["DEBUG login of user karl"]);
+}
+
+mod noisy {
+    pub fn warn_alert() {
+        warn!(target: "{Alert}", "noisy warning - must not be written");
+    }
+}
+
+#[derive(Clone, Default)]
+struct MemWriter(Arc<Mutex<Vec<String>>>);
+
+impl MemWriter {
+    fn lines(&self) -> Vec<String> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl LogWriter for MemWriter {
+    fn write(&self, record: &Record) -> io::Result<()> {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("{} {}", record.level(), record.args()));
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}